@@ -0,0 +1,68 @@
+//! Compares processing minidumps with a persistent `Processor` against
+//! processing every minidump with `ProcessState::from_minidump_buffer`.
+//!
+//! Run with `cargo bench --bench processor`.
+
+extern crate breakpad;
+
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use breakpad::{CodeModuleId, FrameInfoMap, ProcessState, Processor};
+
+const ITERATIONS: u32 = 200;
+
+fn load_fixture(file_name: &str) -> Vec<u8> {
+    let path = Path::new("tests").join("fixtures").join(file_name);
+    let mut buffer = Vec::new();
+    let mut file = File::open(path).expect("Could not open fixture");
+    file.read_to_end(&mut buffer).expect("Could not read fixture");
+    buffer
+}
+
+fn measure<F: FnMut()>(name: &str, mut f: F) {
+    // Warm up caches before measuring
+    f();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+
+    let elapsed = start.elapsed();
+    let per_iteration = elapsed / ITERATIONS;
+    println!(
+        "{:<30} {:>10.3} ms/iter ({} iterations)",
+        name,
+        millis(per_iteration),
+        ITERATIONS
+    );
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1_000_000.0
+}
+
+fn main() {
+    let minidump = load_fixture("crash_macos.dmp");
+    let cfi = load_fixture("crash_macos_cfi.sym");
+
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let mut symbols = FrameInfoMap::new();
+    symbols.insert(module_id, cfi.as_slice());
+
+    measure("ProcessState per call", || {
+        ProcessState::from_minidump_buffer(&minidump, Some(&symbols))
+            .expect("Could not process minidump");
+    });
+
+    let mut processor = Processor::new();
+    measure("Processor with cached CFI", || {
+        processor
+            .process_minidump_buffer(&minidump, Some(&symbols))
+            .expect("Could not process minidump");
+    });
+}
@@ -0,0 +1,137 @@
+#include "google_breakpad/processor/stack_frame.h"
+#include "processor/basic_code_module.h"
+
+#include "cpp/caching_resolver.h"
+
+using google_breakpad::BasicCodeModule;
+using google_breakpad::BasicSourceLineResolver;
+using google_breakpad::CFIFrameInfo;
+using google_breakpad::CodeModule;
+using google_breakpad::StackFrame;
+using google_breakpad::WindowsFrameInfo;
+
+namespace {
+
+// Creates a copy of the module that reports its debug identifier as code file.
+// The inner resolver uses the code file to identify loaded modules.
+std::unique_ptr<CodeModule> key_module(const CodeModule *module) {
+  if (module == nullptr) {
+    return nullptr;
+  }
+
+  return std::unique_ptr<CodeModule>(new BasicCodeModule(
+      module->base_address(), module->size(), module->debug_identifier(),
+      module->code_identifier(), module->debug_file(),
+      module->debug_identifier(), module->version()));
+}
+
+// Creates an empty module that is only used to look up loaded modules by
+// their debug identifier.
+std::unique_ptr<CodeModule> key_module(const std::string &debug_identifier) {
+  return std::unique_ptr<CodeModule>(new BasicCodeModule(
+      0, 0, debug_identifier, "", "", debug_identifier, ""));
+}
+
+// Creates a copy of the frame's instruction that refers to the keyed module.
+// Lookups in the inner resolver only use the instruction and module.
+void key_frame(const StackFrame *frame,
+               const CodeModule *module,
+               StackFrame *keyed_frame) {
+  keyed_frame->instruction = frame->instruction;
+  keyed_frame->module = module;
+  keyed_frame->trust = frame->trust;
+}
+
+}  // namespace
+
+CachingResolver::CachingResolver() : resolver_(new BasicSourceLineResolver()) {
+}
+
+bool CachingResolver::LoadModule(const CodeModule *module,
+                                 const string &map_file) {
+  auto keyed_module = key_module(module);
+  return resolver_->LoadModule(keyed_module.get(), map_file);
+}
+
+bool CachingResolver::LoadModuleUsingMapBuffer(const CodeModule *module,
+                                               const string &map_buffer) {
+  auto keyed_module = key_module(module);
+  return resolver_->LoadModuleUsingMapBuffer(keyed_module.get(), map_buffer);
+}
+
+bool CachingResolver::LoadModuleUsingMemoryBuffer(const CodeModule *module,
+                                                  char *memory_buffer,
+                                                  size_t memory_buffer_size) {
+  auto keyed_module = key_module(module);
+  return resolver_->LoadModuleUsingMemoryBuffer(
+      keyed_module.get(), memory_buffer, memory_buffer_size);
+}
+
+bool CachingResolver::ShouldDeleteMemoryBufferAfterLoadModule() {
+  return resolver_->ShouldDeleteMemoryBufferAfterLoadModule();
+}
+
+void CachingResolver::UnloadModule(const CodeModule *module) {
+  auto keyed_module = key_module(module);
+  resolver_->UnloadModule(keyed_module.get());
+}
+
+bool CachingResolver::HasModule(const CodeModule *module) {
+  auto keyed_module = key_module(module);
+  return resolver_->HasModule(keyed_module.get());
+}
+
+bool CachingResolver::IsModuleCorrupt(const CodeModule *module) {
+  auto keyed_module = key_module(module);
+  return resolver_->IsModuleCorrupt(keyed_module.get());
+}
+
+void CachingResolver::FillSourceLineInfo(StackFrame *frame) {
+  if (frame == nullptr || frame->module == nullptr) {
+    return;
+  }
+
+  // The resolver writes into the frame, so temporarily swap out its module
+  const CodeModule *module = frame->module;
+  auto keyed_module = key_module(module);
+  frame->module = keyed_module.get();
+  resolver_->FillSourceLineInfo(frame);
+  frame->module = module;
+}
+
+WindowsFrameInfo *CachingResolver::FindWindowsFrameInfo(
+    const StackFrame *frame) {
+  if (frame == nullptr || frame->module == nullptr) {
+    return nullptr;
+  }
+
+  StackFrame keyed_frame;
+  auto keyed_module = key_module(frame->module);
+  key_frame(frame, keyed_module.get(), &keyed_frame);
+  return resolver_->FindWindowsFrameInfo(&keyed_frame);
+}
+
+CFIFrameInfo *CachingResolver::FindCFIFrameInfo(const StackFrame *frame) {
+  if (frame == nullptr || frame->module == nullptr) {
+    return nullptr;
+  }
+
+  StackFrame keyed_frame;
+  auto keyed_module = key_module(frame->module);
+  key_frame(frame, keyed_module.get(), &keyed_frame);
+  return resolver_->FindCFIFrameInfo(&keyed_frame);
+}
+
+bool CachingResolver::Evict(const std::string &debug_identifier) {
+  auto keyed_module = key_module(debug_identifier);
+  if (!resolver_->HasModule(keyed_module.get())) {
+    return false;
+  }
+
+  resolver_->UnloadModule(keyed_module.get());
+  return true;
+}
+
+void CachingResolver::Clear() {
+  resolver_.reset(new BasicSourceLineResolver());
+}
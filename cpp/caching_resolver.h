@@ -0,0 +1,62 @@
+#ifndef SENTRY_CACHING_RESOLVER_H
+#define SENTRY_CACHING_RESOLVER_H
+
+#include <memory>
+#include <string>
+
+#include "google_breakpad/processor/basic_source_line_resolver.h"
+#include "google_breakpad/processor/source_line_resolver_interface.h"
+
+/// Source line resolver that retains loaded modules across minidumps.
+///
+/// Breakpad's resolvers identify modules by their code file, which is not
+/// unique across builds of the same library. This resolver wraps a
+/// BasicSourceLineResolver and identifies modules by their debug identifier
+/// instead, so that symbols of one build are never used for another.
+class CachingResolver : public google_breakpad::SourceLineResolverInterface {
+ public:
+  CachingResolver();
+
+  virtual ~CachingResolver() {
+  }
+
+  virtual bool LoadModule(const google_breakpad::CodeModule *module,
+                          const string &map_file);
+
+  virtual bool LoadModuleUsingMapBuffer(
+      const google_breakpad::CodeModule *module,
+      const string &map_buffer);
+
+  virtual bool LoadModuleUsingMemoryBuffer(
+      const google_breakpad::CodeModule *module,
+      char *memory_buffer,
+      size_t memory_buffer_size);
+
+  virtual bool ShouldDeleteMemoryBufferAfterLoadModule();
+
+  virtual void UnloadModule(const google_breakpad::CodeModule *module);
+
+  virtual bool HasModule(const google_breakpad::CodeModule *module);
+
+  virtual bool IsModuleCorrupt(const google_breakpad::CodeModule *module);
+
+  virtual void FillSourceLineInfo(google_breakpad::StackFrame *frame);
+
+  virtual google_breakpad::WindowsFrameInfo *FindWindowsFrameInfo(
+      const google_breakpad::StackFrame *frame);
+
+  virtual google_breakpad::CFIFrameInfo *FindCFIFrameInfo(
+      const google_breakpad::StackFrame *frame);
+
+  /// Unloads the module with the given debug identifier. Returns whether the
+  /// module had been loaded.
+  bool Evict(const std::string &debug_identifier);
+
+  /// Unloads all modules.
+  void Clear();
+
+ private:
+  std::unique_ptr<google_breakpad::BasicSourceLineResolver> resolver_;
+};
+
+#endif
@@ -7,10 +7,11 @@
 #include "google_breakpad/processor/stack_frame.h"
 
 #include "cpp/c_mapping.h"
+#include "cpp/process_context.h"
 
 typedef_extern_c(call_stack_t, google_breakpad::CallStack);
 typedef_extern_c(code_module_t, google_breakpad::CodeModule);
-typedef_extern_c(process_state_t, google_breakpad::ProcessState);
+typedef_extern_c(process_state_t, ProcessContext);
 typedef_extern_c(stack_frame_t, google_breakpad::StackFrame);
 
 #endif
@@ -1,10 +1,89 @@
 #include "google_breakpad/processor/stack_frame.h"
+#include "google_breakpad/processor/stack_frame_cpu.h"
 
 #include "cpp/c_string.h"
 #include "cpp/data_definitions.h"
 #include "cpp/data_structures.h"
 
 using google_breakpad::StackFrame;
+using google_breakpad::StackFrameAMD64;
+using google_breakpad::StackFrameARM;
+using google_breakpad::StackFrameARM64;
+using google_breakpad::StackFrameMIPS;
+using google_breakpad::StackFramePPC;
+using google_breakpad::StackFramePPC64;
+using google_breakpad::StackFrameSPARC;
+using google_breakpad::StackFrameX86;
+
+namespace {
+
+// Keep in sync with CpuFamily in processor.rs
+enum cpu_family_t {
+  CPU_FAMILY_UNKNOWN,
+  CPU_FAMILY_X86,
+  CPU_FAMILY_AMD64,
+  CPU_FAMILY_ARM,
+  CPU_FAMILY_ARM64,
+  CPU_FAMILY_PPC,
+  CPU_FAMILY_PPC64,
+  CPU_FAMILY_SPARC,
+  CPU_FAMILY_MIPS,
+};
+
+// Creates an empty frame of the same CPU-specific type as the given frame and
+// copies its register context. This retains the return address and CPU family
+// of the original frame. Returns null if the frame is not of type T.
+template <typename T>
+StackFrame *clone_cpu_context(const StackFrame *frame) {
+  auto *cpu_frame = dynamic_cast<const T *>(frame);
+  if (cpu_frame == nullptr) {
+    return nullptr;
+  }
+
+  auto *clone = new T();
+  if (clone == nullptr) {
+    return nullptr;
+  }
+
+  clone->context = cpu_frame->context;
+  clone->context_validity = cpu_frame->context_validity;
+  return clone;
+}
+
+StackFrame *clone_stack_frame(const StackFrame *frame) {
+  if (frame == nullptr) {
+    return nullptr;
+  }
+
+  StackFrame *clone = nullptr;
+  if ((clone = clone_cpu_context<StackFrameX86>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFrameAMD64>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFrameARM>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFrameARM64>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFramePPC>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFramePPC64>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFrameSPARC>(frame)) == nullptr &&
+      (clone = clone_cpu_context<StackFrameMIPS>(frame)) == nullptr) {
+    clone = new StackFrame();
+  }
+
+  if (clone == nullptr) {
+    return nullptr;
+  }
+
+  clone->instruction = frame->instruction;
+  clone->module = frame->module;
+  clone->function_name = frame->function_name;
+  clone->function_base = frame->function_base;
+  clone->source_file_name = frame->source_file_name;
+  clone->source_line = frame->source_line;
+  clone->source_line_base = frame->source_line_base;
+  clone->trust = frame->trust;
+
+  return clone;
+}
+
+}  // namespace
 
 void process_state_delete(process_state_t *state) {
   if (state != nullptr) {
@@ -18,7 +97,7 @@ call_stack_t *const *process_state_threads(process_state_t *state,
     return nullptr;
   }
 
-  auto *threads = process_state_t::cast(state)->threads();
+  auto *threads = process_state_t::cast(state)->state().threads();
   if (size_out != nullptr) {
     *size_out = threads->size();
   }
@@ -52,6 +131,37 @@ uint64_t stack_frame_instruction(const stack_frame_t *frame) {
   return stack_frame_t::cast(frame)->instruction;
 }
 
+uint64_t stack_frame_return_address(const stack_frame_t *frame) {
+  if (frame == nullptr) {
+    return 0;
+  }
+
+  return stack_frame_t::cast(frame)->ReturnAddress();
+}
+
+int stack_frame_cpu_family(const stack_frame_t *frame) {
+  const StackFrame *cpp_frame = stack_frame_t::cast(frame);
+  if (dynamic_cast<const StackFrameX86 *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_X86;
+  } else if (dynamic_cast<const StackFrameAMD64 *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_AMD64;
+  } else if (dynamic_cast<const StackFrameARM *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_ARM;
+  } else if (dynamic_cast<const StackFrameARM64 *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_ARM64;
+  } else if (dynamic_cast<const StackFramePPC *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_PPC;
+  } else if (dynamic_cast<const StackFramePPC64 *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_PPC64;
+  } else if (dynamic_cast<const StackFrameSPARC *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_SPARC;
+  } else if (dynamic_cast<const StackFrameMIPS *>(cpp_frame) != nullptr) {
+    return CPU_FAMILY_MIPS;
+  }
+
+  return CPU_FAMILY_UNKNOWN;
+}
+
 const code_module_t *stack_frame_module(const stack_frame_t *frame) {
   if (frame == nullptr) {
     return nullptr;
@@ -68,6 +178,56 @@ int stack_frame_trust(const stack_frame_t *frame) {
   return stack_frame_t::cast(frame)->trust;
 }
 
+const char *stack_frame_function_name(const stack_frame_t *frame) {
+  if (frame == nullptr) {
+    return nullptr;
+  }
+
+  return stack_frame_t::cast(frame)->function_name.c_str();
+}
+
+const char *stack_frame_source_file_name(const stack_frame_t *frame) {
+  if (frame == nullptr) {
+    return nullptr;
+  }
+
+  return stack_frame_t::cast(frame)->source_file_name.c_str();
+}
+
+int stack_frame_source_line(const stack_frame_t *frame) {
+  if (frame == nullptr) {
+    return 0;
+  }
+
+  return stack_frame_t::cast(frame)->source_line;
+}
+
+uint64_t stack_frame_function_base(const stack_frame_t *frame) {
+  if (frame == nullptr) {
+    return 0;
+  }
+
+  return stack_frame_t::cast(frame)->function_base;
+}
+
+uint64_t stack_frame_source_line_base(const stack_frame_t *frame) {
+  if (frame == nullptr) {
+    return 0;
+  }
+
+  return stack_frame_t::cast(frame)->source_line_base;
+}
+
+stack_frame_t *stack_frame_clone(const stack_frame_t *frame) {
+  return stack_frame_t::cast(clone_stack_frame(stack_frame_t::cast(frame)));
+}
+
+void stack_frame_delete(stack_frame_t *frame) {
+  if (frame != nullptr) {
+    delete stack_frame_t::cast(frame);
+  }
+}
+
 uint64_t code_module_base_address(const code_module_t *module) {
   return code_module_t::cast(module)->base_address();
 }
@@ -64,12 +64,23 @@ stack_frame_t *const *call_stack_frames(const call_stack_t *stack,
 ///
 /// On some architectures, the return address as saved on the stack or in
 /// a register is fine for looking up the point of the call. On others, it
-/// requires adjustment. ReturnAddress returns the address as saved by the
-/// machine.
+/// requires adjustment. stack_frame_return_address returns the address as
+/// saved by the machine.
 ///
 /// Use stack_frame_trust to obtain how trustworthy this instruction is.
 uint64_t stack_frame_instruction(const stack_frame_t *frame);
 
+/// Returns the actual return address, as saved on the stack or in a register.
+/// For the innermost frame, this is the same as stack_frame_instruction. For
+/// all other frames, this is the address at which control will resume when the
+/// call returns, before the stackwalker's adjustment into the call instruction.
+uint64_t stack_frame_return_address(const stack_frame_t *frame);
+
+/// Returns the CPU family of the stack frame's context. The stackwalker creates
+/// frames specific to the CPU the minidump was written on, which determines
+/// the adjustment applied to return addresses.
+int stack_frame_cpu_family(const stack_frame_t *frame);
+
 /// Returns a weak pointer to the code module that hosts the instruction of the
 /// stack framme. This function can return null for some frames.
 const code_module_t *stack_frame_module(const stack_frame_t *frame);
@@ -80,6 +91,41 @@ const code_module_t *stack_frame_module(const stack_frame_t *frame);
 /// In rough order of "trust metric".
 int stack_frame_trust(const stack_frame_t *frame);
 
+/// Returns a weak pointer to the function name of the instruction. Can be empty
+/// if no symbols were supplied for the frame's module during stackwalking, or
+/// before running the resolver.
+const char *stack_frame_function_name(const stack_frame_t *frame);
+
+/// Returns a weak pointer to the source code file name in which the
+/// instruction was declared. Can be empty if no symbols were supplied for the
+/// frame's module during stackwalking, or before running the resolver.
+const char *stack_frame_source_file_name(const stack_frame_t *frame);
+
+/// Returns the source code line at which the instruction was declared. Can be
+/// empty if no symbols were supplied for the frame's module during
+/// stackwalking, or before running the resolver.
+int stack_frame_source_line(const stack_frame_t *frame);
+
+/// Returns the absolute start address of the function that contains the
+/// instruction. Zero if no symbols were supplied for the frame's module during
+/// stackwalking, or before running the resolver.
+uint64_t stack_frame_function_base(const stack_frame_t *frame);
+
+/// Returns the absolute start address of the source line record that contains
+/// the instruction. Zero if no symbols were supplied for the frame's module
+/// during stackwalking, or before running the resolver.
+uint64_t stack_frame_source_line_base(const stack_frame_t *frame);
+
+/// Creates a copy of the given stack frame and returns an owning pointer to
+/// it. The copy retains the CPU-specific register context of the frame, as
+/// well as all symbol information added during stackwalking.
+///
+/// Release memory of this frame with the stack_frame_delete function.
+stack_frame_t *stack_frame_clone(const stack_frame_t *frame);
+
+/// Releases memory of a stack frame. Assumes ownership of the pointer.
+void stack_frame_delete(stack_frame_t *frame);
+
 /// Returns the base address of this code module as it was loaded by the
 /// process. (uint64_t)-1 on error.
 uint64_t code_module_base_address(const code_module_t *module);
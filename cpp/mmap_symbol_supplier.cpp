@@ -61,3 +61,7 @@ MmapSymbolSupplier::SymbolResult MmapSymbolSupplier::GetCStringSymbolData(
 void MmapSymbolSupplier::FreeSymbolData(const CodeModule *module) {
   // Nothing to do. Managed by the owner of the supplier
 }
+
+bool MmapSymbolSupplier::HasSymbols(const CodeModule *module) const {
+  return cache.find(module->debug_identifier()) != cache.end();
+}
@@ -34,6 +34,9 @@ class MmapSymbolSupplier : public google_breakpad::SymbolSupplier {
 
   virtual void FreeSymbolData(const google_breakpad::CodeModule *module);
 
+  /// Returns whether this supplier holds symbols for the given module.
+  bool HasSymbols(const google_breakpad::CodeModule *module) const;
+
  private:
   std::map<std::string, const symbol_entry_t *> cache;
 };
@@ -0,0 +1,212 @@
+#include <vector>
+
+#include "google_breakpad/processor/call_stack.h"
+#include "google_breakpad/processor/code_modules.h"
+#include "google_breakpad/processor/minidump_processor.h"
+#include "google_breakpad/processor/stack_frame.h"
+#include "google_breakpad/processor/stack_frame_symbolizer.h"
+#include "google_breakpad/processor/stackwalker.h"
+
+#include "cpp/mmap_symbol_supplier.h"
+#include "cpp/process_context.h"
+
+using google_breakpad::CallStack;
+using google_breakpad::CodeModule;
+using google_breakpad::CodeModules;
+using google_breakpad::MemoryRegion;
+using google_breakpad::MinidumpContext;
+using google_breakpad::MinidumpException;
+using google_breakpad::MinidumpProcessor;
+using google_breakpad::MinidumpThread;
+using google_breakpad::MinidumpThreadList;
+using google_breakpad::StackFrameSymbolizer;
+using google_breakpad::Stackwalker;
+using google_breakpad::SymbolSupplier;
+
+ProcessContext::ProcessContext(
+    const char *buffer,
+    size_t buffer_size,
+    std::shared_ptr<google_breakpad::SourceLineResolverInterface> resolver,
+    bool trace)
+    : buffer_(buffer, buffer_size),
+      stream_(buffer_.data(), buffer_.size()),
+      minidump_(stream_),
+      resolver_(resolver),
+      trace_(trace) {
+}
+
+int ProcessContext::Process(const symbol_entry_t *symbols,
+                            size_t symbol_count) {
+  if (!minidump_.Read()) {
+    return google_breakpad::PROCESS_ERROR_MINIDUMP_NOT_FOUND;
+  }
+
+  MmapSymbolSupplier supplier(symbol_count, symbols);
+  auto symbolizer = CreateSymbolizer(&supplier);
+  MinidumpProcessor processor(symbolizer.get(), false);
+
+  int result = processor.Process(&minidump_, &state_);
+  if (trace_ && result == google_breakpad::PROCESS_OK) {
+    auto *tracing = static_cast<TracingSymbolizer *>(symbolizer.get());
+    std::vector<WalkRecord> walks = tracing->TakeWalks();
+    for (size_t index = 0; index < state_.threads()->size(); ++index) {
+      RecordTrace(index, &walks);
+    }
+  }
+
+  return result;
+}
+
+bool ProcessContext::Rewalk(const size_t *thread_indexes,
+                            size_t thread_count,
+                            const symbol_entry_t *symbols,
+                            size_t symbol_count) {
+  MmapSymbolSupplier supplier(symbol_count, symbols);
+
+  // The resolver never asks the supplier for modules it has already loaded.
+  // Unload them, so that the new symbols take effect for the next walk.
+  const CodeModules *modules = state_.modules();
+  for (unsigned int i = 0; modules != nullptr && i < modules->module_count();
+       ++i) {
+    const CodeModule *module = modules->GetModuleAtIndex(i);
+    if (supplier.HasSymbols(module) && resolver_->HasModule(module)) {
+      resolver_->UnloadModule(module);
+    }
+  }
+
+  // The symbolizer caches modules without symbols, so it must not outlive
+  // this walk.
+  auto symbolizer = CreateSymbolizer(&supplier);
+  bool success = true;
+
+  for (const size_t *index = thread_indexes;
+       index < thread_indexes + thread_count; ++index) {
+    const auto *threads = state_.threads();
+    const auto *memory_regions = state_.thread_memory_regions();
+    if (*index >= threads->size() || *index >= memory_regions->size()) {
+      success = false;
+      continue;
+    }
+
+    MinidumpContext *context = GetThreadContext(*index);
+    MemoryRegion *memory = memory_regions->at(*index);
+    std::unique_ptr<Stackwalker> walker(Stackwalker::StackwalkerForCPU(
+        state_.system_info(), context, memory, state_.modules(),
+        state_.unloaded_modules(), symbolizer.get()));
+
+    if (!walker) {
+      success = false;
+      continue;
+    }
+
+    // Walking clears the stack including its thread id
+    CallStack *stack = threads->at(*index);
+    uint32_t thread_id = stack->tid();
+
+    // Discard walks of previous threads whose frames have been released.
+    if (trace_) {
+      static_cast<TracingSymbolizer *>(symbolizer.get())->TakeWalks();
+    }
+
+    std::vector<const CodeModule *> modules_without_symbols;
+    std::vector<const CodeModule *> modules_with_corrupt_symbols;
+    if (!walker->Walk(stack, &modules_without_symbols,
+                      &modules_with_corrupt_symbols)) {
+      success = false;
+    }
+
+    stack->set_tid(thread_id);
+
+    if (trace_) {
+      auto *tracing = static_cast<TracingSymbolizer *>(symbolizer.get());
+      std::vector<WalkRecord> walks = tracing->TakeWalks();
+      RecordTrace(*index, &walks);
+    }
+  }
+
+  return success;
+}
+
+const ThreadTrace *ProcessContext::GetTrace(size_t index) const {
+  return index < traces_.size() ? traces_[index].get() : nullptr;
+}
+
+MinidumpContext *ProcessContext::GetThreadContext(size_t index) {
+  // The processor uses the exception record's context for the crashed thread,
+  // instead of the thread's own context.
+  if (state_.requesting_thread() == static_cast<int>(index)) {
+    MinidumpException *exception = minidump_.GetException();
+    if (exception != nullptr && exception->GetContext() != nullptr) {
+      return exception->GetContext();
+    }
+  }
+
+  MinidumpThreadList *thread_list = minidump_.GetThreadList();
+  if (thread_list == nullptr) {
+    return nullptr;
+  }
+
+  uint32_t stack_thread_id = state_.threads()->at(index)->tid();
+  for (unsigned int i = 0; i < thread_list->thread_count(); ++i) {
+    MinidumpThread *thread = thread_list->GetThreadAtIndex(i);
+    uint32_t thread_id;
+    if (thread != nullptr && thread->GetThreadID(&thread_id) &&
+        thread_id == stack_thread_id) {
+      return thread->GetContext();
+    }
+  }
+
+  return nullptr;
+}
+
+std::unique_ptr<StackFrameSymbolizer> ProcessContext::CreateSymbolizer(
+    SymbolSupplier *supplier) {
+  if (trace_) {
+    return std::unique_ptr<StackFrameSymbolizer>(
+        new TracingSymbolizer(supplier, resolver_.get()));
+  }
+
+  return std::unique_ptr<StackFrameSymbolizer>(
+      new StackFrameSymbolizer(supplier, resolver_.get()));
+}
+
+void ProcessContext::RecordTrace(size_t index,
+                                 std::vector<WalkRecord> *walks) {
+  const CallStack *stack = state_.threads()->at(index);
+  const auto *frames = stack->frames();
+  std::unique_ptr<ThreadTrace> trace(new ThreadTrace());
+  trace->termination = WALK_TERMINATION_NO_CONTEXT;
+
+  // Every walk begins with the first frame of the stack it produced. Frame
+  // pointers are unique at this point, since the state owns all frames.
+  for (WalkRecord &walk : *walks) {
+    if (!frames->empty() && !walk.frames.empty() &&
+        walk.frames.front() == frames->front()) {
+      trace->lookups = std::move(walk.lookups);
+      trace->termination = walk.termination;
+      break;
+    }
+  }
+
+  for (const FrameLookup &lookup : trace->lookups) {
+    frame_trace_t frame_trace;
+    frame_trace.trust = lookup.trust;
+    frame_trace.unwind_method = lookup.unwind_method;
+    frame_trace.cfi_searched = lookup.cfi_searched;
+    frame_trace.cfi_rules =
+        lookup.cfi_rules.empty() ? nullptr : lookup.cfi_rules.c_str();
+    frame_trace.windows_searched = lookup.windows_searched;
+    frame_trace.windows_type = lookup.windows_type;
+    frame_trace.windows_program_string =
+        lookup.windows_program_string.empty()
+            ? nullptr
+            : lookup.windows_program_string.c_str();
+    trace->frames.push_back(frame_trace);
+  }
+
+  if (traces_.size() <= index) {
+    traces_.resize(index + 1);
+  }
+
+  traces_[index] = std::move(trace);
+}
@@ -0,0 +1,89 @@
+#ifndef SENTRY_PROCESS_CONTEXT_H
+#define SENTRY_PROCESS_CONTEXT_H
+
+#include <cstddef>
+#include <memory>
+#include <string>
+#include <vector>
+
+#include "google_breakpad/processor/call_stack.h"
+#include "google_breakpad/processor/minidump.h"
+#include "google_breakpad/processor/process_state.h"
+#include "google_breakpad/processor/source_line_resolver_interface.h"
+
+#include "cpp/memstream.h"
+#include "cpp/processor.h"
+#include "cpp/tracing_symbolizer.h"
+
+/// Trace of all stackwalker decisions in a single thread. The frame traces
+/// point into the recorded lookups, so this trace must not be copied.
+struct ThreadTrace {
+  std::vector<FrameLookup> lookups;
+  std::vector<frame_trace_t> frames;
+  int termination;
+};
+
+/// Owns a processed minidump along with everything required to walk its
+/// threads again.
+///
+/// The ProcessState references memory regions of the minidump, which in turn
+/// streams from an owned copy of the raw buffer. The resolver retains all
+/// stack frame information loaded while walking, so that later walks only
+/// need to supply symbols for additional modules.
+class ProcessContext {
+ public:
+  /// Creates a context for the given minidump. All symbols supplied to this
+  /// context are loaded into the given resolver, which may be shared with
+  /// other contexts.
+  ProcessContext(
+      const char *buffer,
+      size_t buffer_size,
+      std::shared_ptr<google_breakpad::SourceLineResolverInterface> resolver,
+      bool trace);
+
+  ProcessContext(const ProcessContext &) = delete;
+  ProcessContext &operator=(const ProcessContext &) = delete;
+
+  /// Reads the minidump and walks the stacks of all threads. Returns one of
+  /// google_breakpad::ProcessResult.
+  int Process(const symbol_entry_t *symbols, size_t symbol_count);
+
+  /// Walks the stacks of the threads at the given indexes again. Symbols for
+  /// modules that have been loaded before are replaced by the given symbols.
+  /// Returns false if one of the threads could not be walked.
+  bool Rewalk(const size_t *thread_indexes,
+              size_t thread_count,
+              const symbol_entry_t *symbols,
+              size_t symbol_count);
+
+  /// Returns the stackwalk trace of the thread at the given index. Returns
+  /// null if tracing was disabled when walking the thread.
+  const ThreadTrace *GetTrace(size_t index) const;
+
+  /// Returns the process state created by Process.
+  google_breakpad::ProcessState &state() {
+    return state_;
+  }
+
+ private:
+  google_breakpad::MinidumpContext *GetThreadContext(size_t index);
+
+  /// Creates a symbolizer for walking stacks, which records traces if enabled.
+  std::unique_ptr<google_breakpad::StackFrameSymbolizer> CreateSymbolizer(
+      google_breakpad::SymbolSupplier *supplier);
+
+  /// Records the stackwalk trace of the thread at the given index from the
+  /// walk that produced its call stack. Threads without a matching walk had
+  /// no CPU context.
+  void RecordTrace(size_t index, std::vector<WalkRecord> *walks);
+
+  std::string buffer_;
+  imemstream stream_;
+  google_breakpad::Minidump minidump_;
+  google_breakpad::ProcessState state_;
+  std::shared_ptr<google_breakpad::SourceLineResolverInterface> resolver_;
+  bool trace_;
+  std::vector<std::unique_ptr<ThreadTrace>> traces_;
+};
+
+#endif
@@ -1,50 +1,150 @@
+#include <memory>
+
 #include "google_breakpad/processor/basic_source_line_resolver.h"
-#include "google_breakpad/processor/minidump.h"
-#include "google_breakpad/processor/minidump_processor.h"
-#include "google_breakpad/processor/process_state.h"
 
+#include "cpp/caching_resolver.h"
 #include "cpp/data_definitions.h"
-#include "cpp/memstream.h"
-#include "cpp/mmap_symbol_supplier.h"
+#include "cpp/process_context.h"
 #include "cpp/processor.h"
 
 using google_breakpad::BasicSourceLineResolver;
-using google_breakpad::Minidump;
-using google_breakpad::MinidumpProcessor;
-using google_breakpad::ProcessState;
+using google_breakpad::SourceLineResolverInterface;
+
+namespace {
+
+// Processor state retained across minidumps.
+struct CachingProcessor {
+  CachingProcessor() : resolver(new CachingResolver()), trace(false) {
+  }
+
+  std::shared_ptr<CachingResolver> resolver;
+  bool trace;
+};
+
+process_state_t *process_with_resolver(
+    const char *buffer,
+    size_t buffer_size,
+    symbol_entry_t *symbols,
+    size_t symbol_count,
+    std::shared_ptr<SourceLineResolverInterface> resolver,
+    bool trace,
+    int *result_out) {
+  if (buffer == nullptr) {
+    *result_out = google_breakpad::PROCESS_ERROR_MINIDUMP_NOT_FOUND;
+    return nullptr;
+  }
+
+  ProcessContext *context =
+      new ProcessContext(buffer, buffer_size, resolver, trace);
+  if (context == nullptr) {
+    *result_out = -1;  // Memory allocation issue
+    return nullptr;
+  }
+
+  *result_out = context->Process(symbols, symbol_count);
+  if (*result_out != google_breakpad::PROCESS_OK) {
+    delete context;
+    return nullptr;
+  }
+
+  return process_state_t::cast(context);
+}
+
+}  // namespace
+
+typedef_extern_c(processor_t, CachingProcessor);
 
 process_state_t *process_minidump(const char *buffer,
                                   size_t buffer_size,
                                   symbol_entry_t *symbols,
                                   size_t symbol_count,
                                   int *result_out) {
-  if (buffer == nullptr) {
-    *result_out = google_breakpad::PROCESS_ERROR_MINIDUMP_NOT_FOUND;
-    return nullptr;
-  }
+  auto resolver = std::make_shared<BasicSourceLineResolver>();
+  return process_with_resolver(buffer, buffer_size, symbols, symbol_count,
+                               resolver, false, result_out);
+}
 
-  ProcessState *state = new ProcessState();
+bool process_state_rewalk(process_state_t *state,
+                          const size_t *thread_indexes,
+                          size_t thread_count,
+                          symbol_entry_t *symbols,
+                          size_t symbol_count) {
   if (state == nullptr) {
-    *result_out = -1;  // Memory allocation issue
+    return false;
+  }
+
+  return process_state_t::cast(state)->Rewalk(thread_indexes, thread_count,
+                                              symbols, symbol_count);
+}
+
+processor_t *processor_new() {
+  return processor_t::cast(new CachingProcessor());
+}
+
+void processor_delete(processor_t *processor) {
+  if (processor != nullptr) {
+    delete processor_t::cast(processor);
+  }
+}
+
+process_state_t *processor_process(processor_t *processor,
+                                   const char *buffer,
+                                   size_t buffer_size,
+                                   symbol_entry_t *symbols,
+                                   size_t symbol_count,
+                                   int *result_out) {
+  if (processor == nullptr) {
+    *result_out = -1;
     return nullptr;
   }
 
-  BasicSourceLineResolver resolver;
-  MmapSymbolSupplier supplier(symbol_count, symbols);
-  MinidumpProcessor processor(&supplier, &resolver);
+  auto *cpp_processor = processor_t::cast(processor);
+  return process_with_resolver(buffer, buffer_size, symbols, symbol_count,
+                               cpp_processor->resolver, cpp_processor->trace,
+                               result_out);
+}
 
-  imemstream in(buffer, buffer_size);
-  Minidump minidump(in);
-  if (!minidump.Read()) {
-    *result_out = google_breakpad::PROCESS_ERROR_MINIDUMP_NOT_FOUND;
+bool processor_evict(processor_t *processor, const char *debug_identifier) {
+  if (processor == nullptr || debug_identifier == nullptr) {
+    return false;
+  }
+
+  return processor_t::cast(processor)->resolver->Evict(debug_identifier);
+}
+
+void processor_clear(processor_t *processor) {
+  if (processor != nullptr) {
+    processor_t::cast(processor)->resolver->Clear();
+  }
+}
+
+void processor_set_trace(processor_t *processor, bool trace) {
+  if (processor != nullptr) {
+    processor_t::cast(processor)->trace = trace;
+  }
+}
+
+const frame_trace_t *process_state_trace(const process_state_t *state,
+                                         size_t thread_index,
+                                         size_t *size_out,
+                                         int *termination_out) {
+  if (state == nullptr) {
     return nullptr;
   }
 
-  *result_out = processor.Process(&minidump, state);
-  if (*result_out != google_breakpad::PROCESS_OK) {
-    delete state;
+  const ThreadTrace *trace =
+      process_state_t::cast(state)->GetTrace(thread_index);
+  if (trace == nullptr) {
     return nullptr;
   }
 
-  return process_state_t::cast(state);
+  if (size_out != nullptr) {
+    *size_out = trace->frames.size();
+  }
+
+  if (termination_out != nullptr) {
+    *termination_out = trace->termination;
+  }
+
+  return trace->frames.data();
 }
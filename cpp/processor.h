@@ -20,6 +20,39 @@ struct symbol_entry_t {
   const char *symbol_data;
 };
 
+/// Decisions of the stackwalker at a single frame of a call stack. The
+/// stackwalker looks up unwind information at a frame to find its caller.
+struct frame_trace_t {
+  /// How the stackwalker found this frame. See stack_frame_trust.
+  int trust;
+
+  /// How the stackwalker found the caller of this frame. See unwind_method_t.
+  int unwind_method;
+
+  /// Whether the stackwalker searched STACK CFI records at this frame.
+  bool cfi_searched;
+
+  /// Weak pointer to the STACK CFI rules found at this frame, serialized as
+  /// in a STACK CFI record. Null if no rules were found.
+  const char *cfi_rules;
+
+  /// Whether the stackwalker searched STACK WIN records at this frame.
+  bool windows_searched;
+
+  /// The type of the STACK WIN record found at this frame. -1 if no record
+  /// was found.
+  int windows_type;
+
+  /// Weak pointer to the program string of the STACK WIN record found at this
+  /// frame. Null if there is no program string.
+  const char *windows_program_string;
+};
+
+/// Minidump processor that retains loaded symbols across minidumps. Symbols
+/// are identified by the debug identifier of their code module. Use the
+/// processor_* family of functions to interact with a processor.
+struct processor_t;
+
 /// Reads a minidump from a memory buffer and processes it. Returns an owning
 /// pointer to a process_state_t struct that contains loaded code modules and
 /// call stacks of all threads of the process during the crash.
@@ -34,6 +67,67 @@ process_state_t *process_minidump(const char *buffer,
                                   size_t symbol_count,
                                   int *result_out);
 
+/// Walks the stacks of the given threads of a processed minidump again. The
+/// threads are identified by their index in process_state_threads.
+///
+/// Symbols given to this function are loaded in addition to all symbols that
+/// have been supplied previously. If symbols for a module have already been
+/// loaded, they are replaced. Returns false if one of the threads could not be
+/// walked.
+///
+/// All pointers into the call stacks of the given threads are invalidated by
+/// this function.
+bool process_state_rewalk(process_state_t *state,
+                          const size_t *thread_indexes,
+                          size_t thread_count,
+                          symbol_entry_t *symbols,
+                          size_t symbol_count);
+
+/// Creates a new minidump processor and returns an owning pointer to it.
+///
+/// Release memory of this processor with the processor_delete function.
+processor_t *processor_new();
+
+/// Releases memory of a processor. Assumes ownership of the pointer. Process
+/// states created by this processor remain valid.
+void processor_delete(processor_t *processor);
+
+/// Reads a minidump from a memory buffer and processes it like
+/// process_minidump. Symbols that have been loaded by earlier calls to this
+/// function are reused, and the given symbols are only loaded for code modules
+/// that are not in the cache yet.
+///
+/// Release memory of the process state with process_state_delete.
+process_state_t *processor_process(processor_t *processor,
+                                   const char *buffer,
+                                   size_t buffer_size,
+                                   symbol_entry_t *symbols,
+                                   size_t symbol_count,
+                                   int *result_out);
+
+/// Removes symbols of the code module with the given debug identifier from
+/// the processor's cache. Returns whether symbols had been loaded.
+bool processor_evict(processor_t *processor, const char *debug_identifier);
+
+/// Removes all symbols from the processor's cache.
+void processor_clear(processor_t *processor);
+
+/// Enables or disables recording stackwalk traces for all minidumps processed
+/// after this call. See process_state_trace.
+void processor_set_trace(processor_t *processor, bool trace);
+
+/// Returns a weak pointer to the stackwalk trace of the thread at the given
+/// index in process_state_threads. The trace contains one entry per frame in
+/// the thread's call stack. The number of frames is returned in size_out, and
+/// the reason why stackwalking stopped in termination_out.
+///
+/// Returns null if tracing was not enabled when walking the thread. The
+/// pointer is invalidated when rewalking the thread or deleting the state.
+const frame_trace_t *process_state_trace(const process_state_t *state,
+                                         size_t thread_index,
+                                         size_t *size_out,
+                                         int *termination_out);
+
 #ifdef __cplusplus
 }
 #endif
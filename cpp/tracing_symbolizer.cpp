@@ -0,0 +1,134 @@
+#include "google_breakpad/processor/stack_frame.h"
+#include "google_breakpad/processor/stackwalker.h"
+#include "processor/cfi_frame_info.h"
+#include "processor/windows_frame_info.h"
+
+#include "cpp/tracing_symbolizer.h"
+
+using google_breakpad::CFIFrameInfo;
+using google_breakpad::CodeModules;
+using google_breakpad::SourceLineResolverInterface;
+using google_breakpad::StackFrame;
+using google_breakpad::StackFrameSymbolizer;
+using google_breakpad::Stackwalker;
+using google_breakpad::SymbolSupplier;
+using google_breakpad::SystemInfo;
+using google_breakpad::WindowsFrameInfo;
+
+namespace {
+
+// Determines the method that found a caller from the unwind information looked
+// up at its callee. The stackwalker denotes the method by the caller's trust.
+int GetUnwindMethod(const FrameLookup &callee, int caller_trust) {
+  switch (caller_trust) {
+    case StackFrame::FRAME_TRUST_CFI:
+      // On x86, callers recovered from STACK WIN records are trusted like CFI.
+      // The stackwalker only searches CFI if STACK WIN records did not help.
+      if (callee.windows_type >= 0 && !callee.cfi_searched) {
+        return UNWIND_METHOD_STACK_WIN;
+      }
+      return UNWIND_METHOD_CFI;
+    case StackFrame::FRAME_TRUST_FP:
+      return UNWIND_METHOD_FRAME_POINTER;
+    case StackFrame::FRAME_TRUST_CFI_SCAN:
+      return UNWIND_METHOD_CFI_SCAN;
+    case StackFrame::FRAME_TRUST_SCAN:
+      return UNWIND_METHOD_SCAN;
+    case StackFrame::FRAME_TRUST_PREWALKED:
+      return UNWIND_METHOD_PREWALKED;
+    default:
+      return UNWIND_METHOD_NONE;
+  }
+}
+
+}  // namespace
+
+TracingSymbolizer::TracingSymbolizer(SymbolSupplier *supplier,
+                                     SourceLineResolverInterface *resolver)
+    : StackFrameSymbolizer(supplier, resolver) {
+}
+
+StackFrameSymbolizer::SymbolizerResult TracingSymbolizer::FillSourceLineInfo(
+    const CodeModules *modules,
+    const CodeModules *unloaded_modules,
+    const SystemInfo *system_info,
+    StackFrame *stack_frame) {
+  // Every walk starts with the frame from the thread's CPU context.
+  if (walks_.empty() ||
+      stack_frame->trust == StackFrame::FRAME_TRUST_CONTEXT) {
+    walks_.push_back(WalkRecord());
+  }
+
+  WalkRecord &walk = walks_.back();
+  if (!walk.lookups.empty()) {
+    FrameLookup &callee = walk.lookups.back();
+    callee.unwind_method = GetUnwindMethod(callee, stack_frame->trust);
+  }
+
+  FrameLookup lookup;
+  lookup.trust = stack_frame->trust;
+  walk.frames.push_back(stack_frame);
+  walk.lookups.push_back(lookup);
+
+  // The stackwalker adds this frame to the stack and stops if the stack
+  // exceeds the maximum size. Otherwise, it stops once it finds no caller.
+  walk.termination = walk.frames.size() > Stackwalker::max_frames()
+                         ? WALK_TERMINATION_MAX_FRAMES
+                         : WALK_TERMINATION_NO_CALLER;
+
+  return StackFrameSymbolizer::FillSourceLineInfo(modules, unloaded_modules,
+                                                  system_info, stack_frame);
+}
+
+WindowsFrameInfo *TracingSymbolizer::FindWindowsFrameInfo(
+    const StackFrame *frame) {
+  WindowsFrameInfo *info = StackFrameSymbolizer::FindWindowsFrameInfo(frame);
+
+  FrameLookup *lookup = GetLookup(frame);
+  if (lookup != nullptr) {
+    lookup->windows_searched = true;
+    if (info != nullptr) {
+      lookup->windows_type = info->type_;
+      lookup->windows_program_string = info->program_string;
+    }
+  }
+
+  return info;
+}
+
+CFIFrameInfo *TracingSymbolizer::FindCFIFrameInfo(const StackFrame *frame) {
+  CFIFrameInfo *info = StackFrameSymbolizer::FindCFIFrameInfo(frame);
+
+  FrameLookup *lookup = GetLookup(frame);
+  if (lookup != nullptr) {
+    lookup->cfi_searched = true;
+    if (info != nullptr) {
+      lookup->cfi_rules = info->Serialize();
+    }
+  }
+
+  return info;
+}
+
+std::vector<WalkRecord> TracingSymbolizer::TakeWalks() {
+  std::vector<WalkRecord> walks;
+  walks.swap(walks_);
+  return walks;
+}
+
+FrameLookup *TracingSymbolizer::GetLookup(const StackFrame *frame) {
+  if (walks_.empty()) {
+    return nullptr;
+  }
+
+  // The stackwalker looks up unwind information at the last frame it found,
+  // which is still alive. Frames of earlier walks may have been released.
+  WalkRecord &walk = walks_.back();
+  for (size_t i = walk.frames.size(); i > 0; --i) {
+    if (walk.frames[i - 1] == frame) {
+      return &walk.lookups[i - 1];
+    }
+  }
+
+  return nullptr;
+}
@@ -0,0 +1,119 @@
+#ifndef SENTRY_TRACING_SYMBOLIZER_H
+#define SENTRY_TRACING_SYMBOLIZER_H
+
+#include <string>
+#include <vector>
+
+#include "google_breakpad/processor/stack_frame_symbolizer.h"
+
+/// Reasons for the stackwalker to stop walking a thread. Keep in sync with
+/// WalkTermination in processor.rs.
+///
+/// The stackwalker does not report why it stopped, so the symbolizer assumes
+/// WALK_TERMINATION_NO_CALLER for every walk below the maximum frame count.
+enum walk_termination_t {
+  WALK_TERMINATION_NO_CONTEXT,
+  WALK_TERMINATION_MAX_FRAMES,
+  WALK_TERMINATION_NO_CALLER,
+};
+
+/// Methods the stackwalker uses to find the caller of a frame. Keep in sync
+/// with UnwindMethod in processor.rs.
+enum unwind_method_t {
+  UNWIND_METHOD_NONE,
+  UNWIND_METHOD_STACK_WIN,
+  UNWIND_METHOD_CFI,
+  UNWIND_METHOD_FRAME_POINTER,
+  UNWIND_METHOD_CFI_SCAN,
+  UNWIND_METHOD_SCAN,
+  UNWIND_METHOD_PREWALKED,
+};
+
+/// Records the outcome of all unwind information lookups at a single frame.
+struct FrameLookup {
+  FrameLookup()
+      : trust(0),
+        unwind_method(UNWIND_METHOD_NONE),
+        cfi_searched(false),
+        windows_searched(false),
+        windows_type(-1) {
+  }
+
+  /// How the stackwalker found this frame. See StackFrame::FrameTrust.
+  int trust;
+
+  /// How the stackwalker found the caller of this frame. UNWIND_METHOD_NONE
+  /// if no caller was found.
+  int unwind_method;
+
+  /// Whether the stackwalker searched CFI at this frame.
+  bool cfi_searched;
+
+  /// Serialized CFI rules found at this frame. Empty if none were found.
+  std::string cfi_rules;
+
+  /// Whether the stackwalker searched Windows frame info at this frame.
+  bool windows_searched;
+
+  /// The type of Windows frame info found at this frame. -1 if none was found.
+  int windows_type;
+
+  /// The program string of Windows frame info found at this frame.
+  std::string windows_program_string;
+};
+
+/// Records of a single stackwalk, in the order the frames were found.
+struct WalkRecord {
+  WalkRecord() : termination(WALK_TERMINATION_NO_CONTEXT) {
+  }
+
+  /// The walked frames. These are only used to identify the call stack and
+  /// must not be dereferenced once the walk is complete.
+  std::vector<const google_breakpad::StackFrame *> frames;
+
+  /// Lookups at each of the frames.
+  std::vector<FrameLookup> lookups;
+
+  /// The reason why the stackwalker stopped after the last frame.
+  int termination;
+};
+
+/// Stack frame symbolizer that records the decisions of the stackwalker while
+/// it walks the stacks of threads.
+///
+/// The stackwalker symbolizes every frame right after finding it, and then
+/// looks up unwind information at that frame to recover the registers of its
+/// caller. The symbolizer therefore records each walk as a sequence of frames,
+/// starting a new walk at every context frame.
+class TracingSymbolizer : public google_breakpad::StackFrameSymbolizer {
+ public:
+  TracingSymbolizer(google_breakpad::SymbolSupplier *supplier,
+                    google_breakpad::SourceLineResolverInterface *resolver);
+
+  virtual ~TracingSymbolizer() {
+  }
+
+  virtual SymbolizerResult FillSourceLineInfo(
+      const google_breakpad::CodeModules *modules,
+      const google_breakpad::CodeModules *unloaded_modules,
+      const google_breakpad::SystemInfo *system_info,
+      google_breakpad::StackFrame *stack_frame);
+
+  virtual google_breakpad::WindowsFrameInfo *FindWindowsFrameInfo(
+      const google_breakpad::StackFrame *frame);
+
+  virtual google_breakpad::CFIFrameInfo *FindCFIFrameInfo(
+      const google_breakpad::StackFrame *frame);
+
+  /// Returns all walks recorded since the last call and clears them.
+  std::vector<WalkRecord> TakeWalks();
+
+ private:
+  /// Returns the lookup of the given frame in the current walk, or null if
+  /// the frame was not symbolized during this walk.
+  FrameLookup *GetLookup(const google_breakpad::StackFrame *frame);
+
+  std::vector<WalkRecord> walks_;
+};
+
+#endif
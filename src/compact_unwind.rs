@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use dwarf::format_rules;
+use errors::ErrorKind::ObjectError;
+use errors::Result;
+use symbol_file::StackCfiInitRecord;
+
+const REGULAR_PAGE: u32 = 2;
+const COMPRESSED_PAGE: u32 = 3;
+
+const MODE_MASK: u32 = 0x0F00_0000;
+
+const X86_MODE_BP_FRAME: u32 = 0x0100_0000;
+const X86_MODE_STACK_IMMD: u32 = 0x0200_0000;
+const X86_BP_FRAME_REGISTERS: u32 = 0x0000_7FFF;
+const X86_BP_FRAME_OFFSET: u32 = 0x00FF_0000;
+const X86_FRAMELESS_STACK_SIZE: u32 = 0x00FF_0000;
+const X86_FRAMELESS_REG_COUNT: u32 = 0x0000_1C00;
+const X86_FRAMELESS_REG_PERMUTATION: u32 = 0x0000_03FF;
+
+const ARM64_MODE_FRAMELESS: u32 = 0x0200_0000;
+const ARM64_MODE_FRAME: u32 = 0x0400_0000;
+const ARM64_FRAMELESS_STACK_SIZE: u32 = 0x00FF_F000;
+
+/// Registers saved by x86 compact unwind encodings, indexed from one.
+const X86_REGISTERS: &[&str] = &["", "$ebx", "$ecx", "$edx", "$edi", "$esi", "$ebp"];
+
+/// Registers saved by x86_64 compact unwind encodings, indexed from one.
+const X86_64_REGISTERS: &[&str] = &["", "$rbx", "$r12", "$r13", "$r14", "$r15", "$rbp"];
+
+/// Callee-saved register pairs of ARM64 frames, in the order they are stored.
+const ARM64_REGISTER_PAIRS: &[(&str, &str)] = &[
+    ("x19", "x20"),
+    ("x21", "x22"),
+    ("x23", "x24"),
+    ("x25", "x26"),
+    ("x27", "x28"),
+];
+
+/// Reads STACK CFI records from the compact unwind encodings in a Mach-O
+/// `__unwind_info` section.
+///
+/// Function offsets in this section are relative to the image base, which
+/// matches the addresses in symbol files. Encodings that defer to DWARF CFI
+/// or that cannot be expressed without inspecting the function's code are
+/// skipped. Only `x86`, `x86_64` and `arm64` are supported; other
+/// architectures yield no records.
+pub(crate) fn read_compact_unwind(data: &[u8], arch: &str) -> Result<Vec<StackCfiInitRecord>> {
+    let reader = Reader(data);
+    if reader.u32(0)? != 1 {
+        return Err(ObjectError("unsupported unwind info version".into()).into());
+    }
+
+    let common_offset = reader.u32(4)? as usize;
+    let common_count = reader.u32(8)? as usize;
+    let index_offset = reader.u32(20)? as usize;
+    let index_count = reader.u32(24)? as usize;
+
+    let mut entries = Vec::new();
+    for index in 0..index_count {
+        let entry_offset = index_offset + index * 12;
+        let function_offset = reader.u32(entry_offset)?;
+        let page_offset = reader.u32(entry_offset + 4)? as usize;
+
+        // The last index entry only marks the end of the last function.
+        if index + 1 == index_count {
+            entries.push((function_offset, 0));
+            break;
+        }
+
+        if page_offset == 0 {
+            continue;
+        }
+
+        let entry_page_offset = page_offset + reader.u16(page_offset + 4)? as usize;
+        let entry_count = reader.u16(page_offset + 6)? as usize;
+
+        match reader.u32(page_offset)? {
+            REGULAR_PAGE => for entry in 0..entry_count {
+                let offset = entry_page_offset + entry * 8;
+                entries.push((reader.u32(offset)?, reader.u32(offset + 4)?));
+            },
+            COMPRESSED_PAGE => {
+                let encodings_offset = page_offset + reader.u16(page_offset + 8)? as usize;
+                for entry in 0..entry_count {
+                    let value = reader.u32(entry_page_offset + entry * 4)?;
+                    let encoding_index = (value >> 24) as usize;
+                    let encoding = if encoding_index < common_count {
+                        reader.u32(common_offset + encoding_index * 4)?
+                    } else {
+                        let page_index = encoding_index - common_count;
+                        reader.u32(encodings_offset + page_index * 4)?
+                    };
+
+                    entries.push((function_offset + (value & 0x00FF_FFFF), encoding));
+                }
+            }
+            _ => return Err(ObjectError("invalid unwind info page".into()).into()),
+        }
+    }
+
+    let mut records = Vec::new();
+    for window in entries.windows(2) {
+        let (address, encoding) = window[0];
+        let size = window[1].0.saturating_sub(address);
+        if size == 0 {
+            continue;
+        }
+
+        let rules = match arch {
+            "x86" => x86_rules(encoding, 4, "$esp", "$ebp", X86_REGISTERS),
+            "x86_64" => x86_rules(encoding, 8, "$rsp", "$rbp", X86_64_REGISTERS),
+            "arm64" => arm64_rules(encoding),
+            _ => None,
+        };
+
+        if let Some(rules) = rules {
+            records.push(StackCfiInitRecord {
+                address: address.into(),
+                size: size.into(),
+                rules,
+                deltas: Vec::new(),
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+/// Converts an x86 or x86_64 compact unwind encoding into CFI rules.
+fn x86_rules(
+    encoding: u32,
+    word_size: i64,
+    stack_pointer: &str,
+    frame_pointer: &str,
+    registers: &[&str],
+) -> Option<String> {
+    let mut rules = BTreeMap::new();
+    rules.insert(".ra", format!(".cfa {} + ^", -word_size));
+
+    let cfa = match encoding & MODE_MASK {
+        X86_MODE_BP_FRAME => {
+            rules.insert(frame_pointer, format!(".cfa {} + ^", -2 * word_size));
+
+            // Registers are saved below the frame pointer, starting at the
+            // given offset in words.
+            let offset = i64::from((encoding & X86_BP_FRAME_OFFSET) >> 16);
+            let saved = encoding & X86_BP_FRAME_REGISTERS;
+            for slot in 0..5 {
+                let register = ((saved >> (3 * slot)) & 0x7) as usize;
+                if register == 0 {
+                    continue;
+                }
+
+                let location = -2 * word_size - offset * word_size + slot * word_size;
+                rules.insert(registers.get(register)?, format!(".cfa {} + ^", location));
+            }
+
+            format!("{} {} +", frame_pointer, 2 * word_size)
+        }
+        X86_MODE_STACK_IMMD => {
+            let stack_size = i64::from((encoding & X86_FRAMELESS_STACK_SIZE) >> 16) * word_size;
+            let count = ((encoding & X86_FRAMELESS_REG_COUNT) >> 10) as usize;
+            let permutation = encoding & X86_FRAMELESS_REG_PERMUTATION;
+
+            let saved = decode_permutation(count, permutation)?;
+            for (slot, &register) in saved.iter().enumerate() {
+                let location = -word_size * (1 + count as i64) + slot as i64 * word_size;
+                rules.insert(registers.get(register)?, format!(".cfa {} + ^", location));
+            }
+
+            format!("{} {} +", stack_pointer, stack_size)
+        }
+        _ => return None,
+    };
+
+    Some(format_rules(Some(&cfa), &rules))
+}
+
+/// Decodes the order of saved registers in a frameless x86 encoding.
+///
+/// The registers are stored as a permutation of up to six registers encoded
+/// in a factorial number system, as implemented in libunwind.
+fn decode_permutation(count: usize, mut permutation: u32) -> Option<Vec<usize>> {
+    let factors: &[u32] = match count {
+        0 => &[],
+        1 => &[1],
+        2 => &[5, 1],
+        3 => &[20, 4, 1],
+        4 => &[60, 12, 3, 1],
+        5 => &[120, 24, 6, 2, 1],
+        6 => &[120, 24, 6, 2, 1, 1],
+        _ => return None,
+    };
+
+    let mut indexes = Vec::with_capacity(count);
+    for &factor in factors {
+        let index = permutation / factor;
+        permutation -= index * factor;
+        indexes.push(index as usize);
+    }
+
+    let mut used = [false; 7];
+    let mut registers = Vec::with_capacity(count);
+    for index in indexes {
+        let register = (1..7).filter(|&r| !used[r]).nth(index)?;
+        used[register] = true;
+        registers.push(register);
+    }
+
+    Some(registers)
+}
+
+/// Converts an ARM64 compact unwind encoding into CFI rules.
+fn arm64_rules(encoding: u32) -> Option<String> {
+    let mut rules = BTreeMap::new();
+
+    let cfa = match encoding & MODE_MASK {
+        ARM64_MODE_FRAMELESS => {
+            let stack_size = ((encoding & ARM64_FRAMELESS_STACK_SIZE) >> 12) * 16;
+            rules.insert(".ra", "x30".to_string());
+            format!("sp {} +", stack_size)
+        }
+        ARM64_MODE_FRAME => {
+            rules.insert(".ra", ".cfa -8 + ^".to_string());
+            rules.insert("x29", ".cfa -16 + ^".to_string());
+
+            let mut location = -24;
+            for (bit, &(first, second)) in ARM64_REGISTER_PAIRS.iter().enumerate() {
+                if encoding & (1 << bit) != 0 {
+                    rules.insert(first, format!(".cfa {} + ^", location));
+                    rules.insert(second, format!(".cfa {} + ^", location - 8));
+                    location -= 16;
+                }
+            }
+
+            "x29 16 +".to_string()
+        }
+        _ => return None,
+    };
+
+    Some(format_rules(Some(&cfa), &rules))
+}
+
+/// Bounds-checked little-endian reads from the unwind info section.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8]> {
+        match self.0.get(offset..offset + len) {
+            Some(bytes) => Ok(bytes),
+            None => Err(ObjectError("unwind info out of bounds".into()).into()),
+        }
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        let bytes = self.bytes(offset, 2)?;
+        Ok(u16::from(bytes[0]) | u16::from(bytes[1]) << 8)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        let bytes = self.bytes(offset, 4)?;
+        Ok(bytes
+            .iter()
+            .rev()
+            .fold(0, |value, &byte| value << 8 | u32::from(byte)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_frameless_permutation() {
+        // rbx, r12 and rbp pushed in this order.
+        assert_eq!(decode_permutation(3, 3), Some(vec![1, 2, 6]));
+        assert_eq!(decode_permutation(0, 0), Some(vec![]));
+        assert_eq!(decode_permutation(7, 0), None);
+    }
+
+    #[test]
+    fn convert_x86_64_encodings() {
+        assert_eq!(
+            x86_rules(0x0100_0000, 8, "$rsp", "$rbp", X86_64_REGISTERS).unwrap(),
+            ".cfa: $rbp 16 + $rbp: .cfa -16 + ^ .ra: .cfa -8 + ^"
+        );
+
+        // Frameless with 0x28 bytes of stack and rbx saved.
+        assert_eq!(
+            x86_rules(0x0205_0400, 8, "$rsp", "$rbp", X86_64_REGISTERS).unwrap(),
+            ".cfa: $rsp 40 + $rbx: .cfa -16 + ^ .ra: .cfa -8 + ^"
+        );
+
+        assert_eq!(x86_rules(0x0400_0038, 8, "$rsp", "$rbp", X86_64_REGISTERS), None);
+    }
+}
@@ -0,0 +1,195 @@
+use std::borrow::Cow;
+
+use cpp_demangle;
+use rustc_demangle;
+
+/// The programming language of a symbol, as inferred from its mangling scheme.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Language {
+    /// C++ symbol mangled according to the Itanium C++ ABI.
+    Cpp,
+    /// Rust symbol in the legacy or v0 mangling scheme.
+    Rust,
+    /// Swift symbol.
+    Swift,
+    /// The name is not mangled, or the mangling scheme is not recognized.
+    Unknown,
+}
+
+impl Language {
+    /// Detects the language of a symbol from its mangling scheme.
+    ///
+    /// Symbols may carry an additional leading underscore, as is common on
+    /// Apple platforms.
+    pub fn detect(name: &str) -> Language {
+        let stripped = strip_apple_prefix(name);
+        if is_v0_rust(stripped) || is_legacy_rust(stripped) {
+            Language::Rust
+        } else if stripped.starts_with("_Z") {
+            Language::Cpp
+        } else if is_swift(name) {
+            Language::Swift
+        } else {
+            Language::Unknown
+        }
+    }
+}
+
+/// Options for demangling symbol names.
+///
+/// The default options demangle names completely, including function
+/// arguments and the hash suffix of Rust symbols.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct DemangleOptions {
+    /// Whether to include the argument list of C++ functions.
+    pub arguments: bool,
+    /// Whether to include the hash suffix of legacy Rust symbols, such as
+    /// `::h0123456789abcdef`.
+    pub rust_hash: bool,
+}
+
+impl Default for DemangleOptions {
+    fn default() -> DemangleOptions {
+        DemangleOptions {
+            arguments: true,
+            rust_hash: true,
+        }
+    }
+}
+
+/// Demangles a C++ or Rust symbol name.
+///
+/// Returns the input unchanged if it is not mangled or cannot be demangled.
+/// Swift symbols are detected by `Language::detect`, but there is no Swift
+/// demangler available, so they are always returned verbatim.
+pub fn demangle<'a>(name: &'a str, options: &DemangleOptions) -> Cow<'a, str> {
+    let demangled = match Language::detect(name) {
+        Language::Rust => demangle_rust(name, options),
+        Language::Cpp => demangle_cpp(name, options),
+        Language::Swift | Language::Unknown => None,
+    };
+
+    match demangled {
+        Some(demangled) => Cow::Owned(demangled),
+        None => Cow::Borrowed(name),
+    }
+}
+
+fn demangle_rust(name: &str, options: &DemangleOptions) -> Option<String> {
+    let demangled = rustc_demangle::try_demangle(name).ok()?;
+    Some(if options.rust_hash {
+        format!("{}", demangled)
+    } else {
+        format!("{:#}", demangled)
+    })
+}
+
+fn demangle_cpp(name: &str, options: &DemangleOptions) -> Option<String> {
+    let symbol = cpp_demangle::Symbol::new(strip_apple_prefix(name)).ok()?;
+    let mut cpp_options = cpp_demangle::DemangleOptions::new();
+    if !options.arguments {
+        cpp_options = cpp_options.no_params();
+    }
+
+    symbol.demangle(&cpp_options).ok()
+}
+
+/// Removes the additional leading underscore that Apple platforms prepend to
+/// C and C++ symbols.
+fn strip_apple_prefix(name: &str) -> &str {
+    if name.starts_with("__") {
+        &name[1..]
+    } else {
+        name
+    }
+}
+
+/// Checks for a legacy Rust symbol, which is a C++ mangled path ending in a
+/// hash component such as `17h0123456789abcdefE`.
+fn is_legacy_rust(name: &str) -> bool {
+    if !name.starts_with("_ZN") || !name.ends_with('E') || name.len() < 24 {
+        return false;
+    }
+
+    // Names may contain arbitrary characters, so compare bytes to avoid
+    // slicing inside a multibyte character.
+    let bytes = name.as_bytes();
+    let hash = &bytes[bytes.len() - 20..bytes.len() - 1];
+    hash.starts_with(b"17h") && hash[3..].iter().all(u8::is_ascii_hexdigit)
+}
+
+/// Checks for a Rust symbol in the v0 mangling scheme, which starts with `_R`
+/// followed by an uppercase tag such as `N` for a nested path.
+fn is_v0_rust(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() > 2 && bytes.starts_with(b"_R") && bytes[2].is_ascii_uppercase()
+}
+
+/// Checks for a Swift symbol, which starts with `$s` (Swift 5), `$S`
+/// (Swift 4.2) or `_T0` (Swift 4), optionally preceded by an underscore.
+fn is_swift(name: &str) -> bool {
+    name.starts_with("_T0") || {
+        let name = name.strip_prefix('_').unwrap_or(name);
+        name.starts_with("$s") || name.starts_with("$S")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_languages() {
+        assert_eq!(
+            Language::detect("_ZN4core9panicking5panic17h5a0b3c4d5e6f7a8bE"),
+            Language::Rust
+        );
+        assert_eq!(Language::detect("__ZN3foo3barEv"), Language::Cpp);
+        assert_eq!(Language::detect("_RNvCs1234_7mycrate3foo"), Language::Rust);
+        assert_eq!(Language::detect("__Random"), Language::Unknown);
+        assert_eq!(Language::detect("_$s4main3fooyyF"), Language::Swift);
+        assert_eq!(Language::detect("$s4main3fooyyF"), Language::Swift);
+        assert_eq!(Language::detect("_$S4main3fooyyF"), Language::Swift);
+        assert_eq!(Language::detect("_T04main3fooyyF"), Language::Swift);
+        assert_eq!(Language::detect("main"), Language::Unknown);
+    }
+
+    #[test]
+    fn detect_multibyte_names() {
+        // The hash position of legacy Rust symbols starts inside a character.
+        assert_eq!(
+            Language::detect("_ZN3fooaaaaaaaaaaaaaaaaaaaaaaaaaaaaaééééééééééE"),
+            Language::Cpp
+        );
+        assert_eq!(Language::detect("_ZN3fooooé7h0123456789abcdefE"), Language::Cpp);
+    }
+
+    #[test]
+    fn demangle_with_options() {
+        let rust = "_ZN4core9panicking5panic17h5a0b3c4d5e6f7a8bE";
+        let options = DemangleOptions::default();
+        assert_eq!(
+            demangle(rust, &options),
+            "core::panicking::panic::h5a0b3c4d5e6f7a8b"
+        );
+
+        let options = DemangleOptions {
+            rust_hash: false,
+            ..Default::default()
+        };
+        assert_eq!(demangle(rust, &options), "core::panicking::panic");
+
+        let cpp = "__ZN3foo3barEi";
+        assert_eq!(demangle(cpp, &DemangleOptions::default()), "foo::bar(int)");
+
+        let options = DemangleOptions {
+            arguments: false,
+            ..Default::default()
+        };
+        assert_eq!(demangle(cpp, &options), "foo::bar");
+
+        let swift = "_$s4main3fooyyF";
+        assert_eq!(Language::detect(swift), Language::Swift);
+        assert_eq!(demangle(swift, &options), swift);
+    }
+}
@@ -0,0 +1,564 @@
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+
+use gimli::{self, AttributeValue, BaseAddresses, CfaRule, CieOrFde, DebuggingInformationEntry,
+            EndianSlice, Register, RegisterRule, RunTimeEndian, SectionId,
+            UninitializedUnwindContext, UnitSectionOffset, UnwindSection};
+
+use demangle::{demangle, DemangleOptions};
+use errors::Result;
+use symbol_file::{FileRecord, FuncRecord, LineRecord, StackCfiInitRecord, StackCfiRecord};
+
+type Slice<'a> = EndianSlice<'a, RunTimeEndian>;
+type Unit<'a> = gimli::Unit<Slice<'a>>;
+type Dwarf<'a> = gimli::Dwarf<Slice<'a>>;
+
+/// Maximum number of `DW_AT_specification` or `DW_AT_abstract_origin`
+/// references followed when looking up a function name.
+const MAX_NAME_REFERENCES: usize = 8;
+
+const X86_REGISTERS: &[&str] = &[
+    "$eax", "$ecx", "$edx", "$ebx", "$esp", "$ebp", "$esi", "$edi", "$eip"
+];
+
+const X86_64_REGISTERS: &[&str] = &[
+    "$rax", "$rdx", "$rcx", "$rbx", "$rsi", "$rdi", "$rbp", "$rsp", "$r8", "$r9", "$r10", "$r11",
+    "$r12", "$r13", "$r14", "$r15", "$rip",
+];
+
+const ARM_REGISTERS: &[&str] = &[
+    "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12", "sp", "lr",
+    "pc",
+];
+
+const ARM64_REGISTERS: &[&str] = &[
+    "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13", "x14",
+    "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26", "x27",
+    "x28", "x29", "x30", "sp",
+];
+
+/// FUNC, line and FILE records collected from DWARF debug information.
+#[derive(Debug, Default)]
+pub(crate) struct DebugInfo {
+    pub files: Vec<FileRecord>,
+    pub functions: Vec<FuncRecord>,
+}
+
+/// Assigns FILE record identifiers to source file paths.
+#[derive(Debug, Default)]
+struct FileTable {
+    ids: HashMap<String, u32>,
+    records: Vec<FileRecord>,
+}
+
+impl FileTable {
+    fn insert(&mut self, name: String) -> u32 {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+
+        let id = self.records.len() as u32;
+        self.ids.insert(name.clone(), id);
+        self.records.push(FileRecord { id, name });
+        id
+    }
+}
+
+/// A contiguous range of instructions mapped to a source line.
+#[derive(Debug)]
+struct LineRange {
+    address: u64,
+    end: u64,
+    file_index: u64,
+    line: u32,
+}
+
+/// Reads functions and line information from the DWARF sections returned by
+/// `load`.
+///
+/// All addresses are made relative to `load_address`. Functions outside of
+/// the loaded image, such as ones removed by the linker, are skipped.
+pub(crate) fn read_debug_info<'a, F>(
+    load: F,
+    endian: RunTimeEndian,
+    load_address: u64,
+) -> Result<DebugInfo>
+where
+    F: Fn(SectionId) -> &'a [u8],
+{
+    let dwarf = Dwarf::load(
+        |id| -> gimli::Result<_> { Ok(EndianSlice::new(load(id), endian)) },
+        |_| -> gimli::Result<_> { Ok(EndianSlice::new(&[], endian)) },
+    )?;
+
+    let mut files = FileTable::default();
+    let mut functions = Vec::new();
+
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
+        let unit = dwarf.unit(header)?;
+        let lines = read_lines(&unit, load_address)?;
+        let mut unit_files = HashMap::new();
+
+        let mut scopes: Vec<(isize, String)> = Vec::new();
+        let mut depth = 0;
+        let mut entries = unit.entries();
+        while let Some((delta, entry)) = entries.next_dfs()? {
+            depth += delta;
+            while scopes.last().is_some_and(|&(d, _)| d >= depth) {
+                scopes.pop();
+            }
+
+            let scope = scopes.last().map(|scope| scope.1.as_str());
+            match entry.tag() {
+                gimli::DW_TAG_namespace
+                | gimli::DW_TAG_class_type
+                | gimli::DW_TAG_structure_type
+                | gimli::DW_TAG_union_type => {
+                    let name = match entry_string(&dwarf, &unit, entry, gimli::DW_AT_name)? {
+                        Some(name) => name,
+                        None if entry.tag() == gimli::DW_TAG_namespace => {
+                            "(anonymous namespace)".into()
+                        }
+                        None => continue,
+                    };
+
+                    let qualified = match scope {
+                        Some(scope) => format!("{}::{}", scope, name),
+                        None => name,
+                    };
+                    scopes.push((depth, qualified));
+                }
+                gimli::DW_TAG_subprogram => {
+                    let name = match function_name(&dwarf, &unit, entry, scope)? {
+                        Some(name) => name,
+                        None => continue,
+                    };
+
+                    let mut ranges = dwarf.die_ranges(&unit, entry)?;
+                    while let Some(range) = ranges.next()? {
+                        let address = match range.begin.checked_sub(load_address) {
+                            Some(address) if range.begin != 0 && range.end > range.begin => {
+                                address
+                            }
+                            _ => continue,
+                        };
+
+                        let size = range.end - range.begin;
+                        let mut function = FuncRecord {
+                            multiple: false,
+                            address,
+                            size,
+                            parameter_size: 0,
+                            name: name.clone(),
+                            inlines: Vec::new(),
+                            lines: Vec::new(),
+                        };
+
+                        for line in function_lines(&lines, address, address + size) {
+                            let file_id = match unit_files.get(&line.file_index) {
+                                Some(&file_id) => file_id,
+                                None => {
+                                    let path = file_path(&dwarf, &unit, line.file_index)?;
+                                    let file_id = files.insert(path);
+                                    unit_files.insert(line.file_index, file_id);
+                                    file_id
+                                }
+                            };
+
+                            let start = cmp::max(line.address, address);
+                            let end = cmp::min(line.end, address + size);
+                            function.lines.push(LineRecord {
+                                address: start,
+                                size: end - start,
+                                line: line.line,
+                                file_id,
+                            });
+                        }
+
+                        functions.push(function);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    functions.sort_by_key(|function| function.address);
+    functions.dedup_by_key(|function| function.address);
+
+    Ok(DebugInfo {
+        files: files.records,
+        functions,
+    })
+}
+
+/// Returns whether a function covers the given address. The functions must be
+/// sorted by address and must not overlap, as returned by `read_debug_info`.
+pub(crate) fn covers_address(functions: &[FuncRecord], address: u64) -> bool {
+    let function = match functions.binary_search_by_key(&address, |function| function.address) {
+        Ok(index) => &functions[index],
+        Err(0) => return false,
+        Err(index) => &functions[index - 1],
+    };
+
+    address - function.address < function.size
+}
+
+/// Runs the line number program of a unit and returns its line ranges,
+/// ordered by address.
+fn read_lines(unit: &Unit, load_address: u64) -> Result<Vec<LineRange>> {
+    let program = match unit.line_program {
+        Some(ref program) => program.clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut lines: Vec<LineRange> = Vec::new();
+    let mut previous = None;
+    let mut rows = program.rows();
+    while let Some((_, row)) = rows.next_row()? {
+        if let Some((address, file_index, line)) = previous.take() {
+            let end = row.address().saturating_sub(load_address);
+            if end > address {
+                let extend = match lines.last() {
+                    Some(last) => {
+                        last.end == address && last.file_index == file_index && last.line == line
+                    }
+                    None => false,
+                };
+
+                if extend {
+                    lines.last_mut().unwrap().end = end;
+                } else {
+                    lines.push(LineRange {
+                        address,
+                        end,
+                        file_index,
+                        line,
+                    });
+                }
+            }
+        }
+
+        if !row.end_sequence() {
+            if let Some(address) = row.address().checked_sub(load_address) {
+                let line = row.line().unwrap_or(0) as u32;
+                previous = Some((address, row.file_index(), line));
+            }
+        }
+    }
+
+    lines.sort_by_key(|line| line.address);
+    Ok(lines)
+}
+
+/// Returns all line ranges overlapping the given address range.
+fn function_lines(lines: &[LineRange], start: u64, end: u64) -> &[LineRange] {
+    let first = match lines.binary_search_by_key(&start, |line| line.address) {
+        Ok(index) => index,
+        Err(index) if index > 0 && lines[index - 1].end > start => index - 1,
+        Err(index) => index,
+    };
+
+    let count = lines[first..]
+        .iter()
+        .take_while(|line| line.address < end)
+        .count();
+
+    &lines[first..first + count]
+}
+
+/// Resolves the full path of a file in the line number program of a unit.
+fn file_path(dwarf: &Dwarf, unit: &Unit, file_index: u64) -> Result<String> {
+    let header = match unit.line_program {
+        Some(ref program) => program.header(),
+        None => return Ok(String::new()),
+    };
+
+    let file = match header.file(file_index) {
+        Some(file) => file,
+        None => return Ok(String::new()),
+    };
+
+    let name = attr_string(dwarf, unit, file.path_name())?;
+    if name.starts_with('/') {
+        return Ok(name);
+    }
+
+    let mut directory = match file.directory(header) {
+        Some(directory) => attr_string(dwarf, unit, directory)?,
+        None => String::new(),
+    };
+
+    if !directory.starts_with('/') {
+        if let Some(ref comp_dir) = unit.comp_dir {
+            directory = join_path(&comp_dir.to_string_lossy(), &directory);
+        }
+    }
+
+    Ok(join_path(&directory, &name))
+}
+
+fn join_path(base: &str, path: &str) -> String {
+    if base.is_empty() {
+        path.into()
+    } else if path.is_empty() {
+        base.into()
+    } else if base.ends_with('/') {
+        format!("{}{}", base, path)
+    } else {
+        format!("{}/{}", base, path)
+    }
+}
+
+fn attr_string(dwarf: &Dwarf, unit: &Unit, value: AttributeValue<Slice>) -> Result<String> {
+    let string = dwarf.attr_string(unit, value)?;
+    Ok(string.to_string_lossy().into_owned())
+}
+
+fn entry_string(
+    dwarf: &Dwarf,
+    unit: &Unit,
+    entry: &DebuggingInformationEntry<Slice>,
+    name: gimli::DwAt,
+) -> Result<Option<String>> {
+    match entry.attr_value(name)? {
+        Some(value) => Ok(Some(attr_string(dwarf, unit, value)?)),
+        None => Ok(None),
+    }
+}
+
+/// Returns the demangled name of a subprogram.
+///
+/// The linkage name is preferred. Otherwise, the plain name is qualified with
+/// the enclosing namespaces and types. Declarations and abstract instances
+/// referenced by the entry are searched if it does not carry a name itself.
+fn function_name(
+    dwarf: &Dwarf,
+    unit: &Unit,
+    entry: &DebuggingInformationEntry<Slice>,
+    scope: Option<&str>,
+) -> Result<Option<String>> {
+    let mut entry = entry.clone();
+    let mut scope = scope;
+
+    for _ in 0..MAX_NAME_REFERENCES {
+        for &attr in &[gimli::DW_AT_linkage_name, gimli::DW_AT_MIPS_linkage_name] {
+            if let Some(name) = entry_string(dwarf, unit, &entry, attr)? {
+                let options = DemangleOptions::default();
+                return Ok(Some(demangle(&name, &options).into_owned()));
+            }
+        }
+
+        if let Some(name) = entry_string(dwarf, unit, &entry, gimli::DW_AT_name)? {
+            return Ok(Some(match scope {
+                Some(scope) => format!("{}::{}", scope, name),
+                None => name,
+            }));
+        }
+
+        let reference = match entry.attr_value(gimli::DW_AT_specification)? {
+            Some(reference) => Some(reference),
+            None => entry.attr_value(gimli::DW_AT_abstract_origin)?,
+        };
+
+        // References to other units are not supported.
+        let offset = match reference {
+            Some(AttributeValue::UnitRef(offset)) => Some(offset),
+            Some(AttributeValue::DebugInfoRef(offset)) => {
+                UnitSectionOffset::DebugInfoOffset(offset).to_unit_offset(unit)
+            }
+            _ => None,
+        };
+
+        entry = match offset {
+            Some(offset) => unit.entry(offset)?,
+            None => return Ok(None),
+        };
+
+        // The declaration may live in a different scope, which is not known.
+        scope = None;
+    }
+
+    Ok(None)
+}
+
+/// Returns the Breakpad name of a DWARF register, if the architecture is
+/// supported.
+fn register_name(arch: &str, register: Register) -> Option<&'static str> {
+    let names = match arch {
+        "x86" => X86_REGISTERS,
+        "x86_64" => X86_64_REGISTERS,
+        "arm" | "armv6" | "armv7" | "armv7s" | "armv7k" => ARM_REGISTERS,
+        "arm64" => ARM64_REGISTERS,
+        _ => return None,
+    };
+
+    names.get(register.0 as usize).cloned()
+}
+
+/// Converts a CFA rule into a postfix expression.
+fn cfa_rule(arch: &str, rule: &CfaRule<Slice>) -> Option<String> {
+    match *rule {
+        CfaRule::RegisterAndOffset { register, offset } => {
+            Some(format!("{} {} +", register_name(arch, register)?, offset))
+        }
+        CfaRule::Expression(_) => None,
+    }
+}
+
+/// Converts a register rule into a postfix expression. Returns `None` for
+/// undefined registers and for DWARF expressions.
+fn register_rule(arch: &str, register: Register, rule: &RegisterRule<Slice>) -> Option<String> {
+    match *rule {
+        RegisterRule::SameValue => register_name(arch, register).map(String::from),
+        RegisterRule::Offset(offset) => Some(format!(".cfa {} + ^", offset)),
+        RegisterRule::ValOffset(offset) => Some(format!(".cfa {} +", offset)),
+        RegisterRule::Register(other) => register_name(arch, other).map(String::from),
+        _ => None,
+    }
+}
+
+/// Formats CFI rules, starting with the CFA rule.
+pub(crate) fn format_rules(cfa: Option<&str>, rules: &BTreeMap<&str, String>) -> String {
+    let mut formatted = String::new();
+    if let Some(cfa) = cfa {
+        formatted.push_str(".cfa: ");
+        formatted.push_str(cfa);
+    }
+
+    for (name, rule) in rules {
+        if !formatted.is_empty() {
+            formatted.push(' ');
+        }
+
+        formatted.push_str(name);
+        formatted.push_str(": ");
+        formatted.push_str(rule);
+    }
+
+    formatted
+}
+
+/// Reads STACK CFI records from an `.eh_frame` or `.debug_frame` section.
+///
+/// Frame description entries with rules that cannot be expressed in
+/// Breakpad's postfix notation are skipped, as well as entries outside of
+/// the loaded image.
+pub(crate) fn read_cfi<'a, S>(
+    section: &S,
+    bases: &BaseAddresses,
+    arch: &str,
+    load_address: u64,
+) -> Result<Vec<StackCfiInitRecord>>
+where
+    S: UnwindSection<Slice<'a>>,
+{
+    let mut records = Vec::new();
+    let mut context = UninitializedUnwindContext::new();
+
+    let mut entries = section.entries(bases);
+    while let Some(entry) = entries.next()? {
+        let partial = match entry {
+            CieOrFde::Cie(_) => continue,
+            CieOrFde::Fde(partial) => partial,
+        };
+
+        let fde = match partial.parse(|s, b, offset| s.cie_from_offset(b, offset)) {
+            Ok(fde) => fde,
+            Err(_) => continue,
+        };
+
+        if fde.initial_address() < load_address || fde.initial_address() == 0 || fde.len() == 0 {
+            continue;
+        }
+
+        let return_address = fde.cie().return_address_register();
+        if let Ok(Some(record)) =
+            read_fde_rows(section, bases, &mut context, &fde, arch, return_address, load_address)
+        {
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+fn read_fde_rows<'a, S>(
+    section: &S,
+    bases: &BaseAddresses,
+    context: &mut UninitializedUnwindContext<Slice<'a>>,
+    fde: &gimli::FrameDescriptionEntry<Slice<'a>>,
+    arch: &str,
+    return_address: Register,
+    load_address: u64,
+) -> Result<Option<StackCfiInitRecord>>
+where
+    S: UnwindSection<Slice<'a>>,
+{
+    let mut init: Option<StackCfiInitRecord> = None;
+    let mut previous_cfa = String::new();
+    let mut previous_rules = BTreeMap::new();
+
+    let mut rows = fde.rows(section, bases, context)?;
+    while let Some(row) = rows.next_row()? {
+        let cfa = match cfa_rule(arch, row.cfa()) {
+            Some(cfa) => cfa,
+            None => return Ok(None),
+        };
+
+        let mut rules = BTreeMap::new();
+        for &(register, ref rule) in row.registers() {
+            let name = if register == return_address {
+                ".ra"
+            } else {
+                match register_name(arch, register) {
+                    Some(name) => name,
+                    None => continue,
+                }
+            };
+
+            if let Some(rule) = register_rule(arch, register, rule) {
+                rules.insert(name, rule);
+            }
+        }
+
+        let address = row.start_address() - load_address;
+        match init {
+            None => {
+                init = Some(StackCfiInitRecord {
+                    address,
+                    size: fde.len(),
+                    rules: format_rules(Some(&cfa), &rules),
+                    deltas: Vec::new(),
+                });
+            }
+            Some(ref mut init) => {
+                let changed_cfa = if cfa != previous_cfa {
+                    Some(cfa.as_str())
+                } else {
+                    None
+                };
+
+                let changed_rules = rules
+                    .iter()
+                    .filter(|&(name, rule)| previous_rules.get(name) != Some(rule))
+                    .map(|(&name, rule)| (name, rule.clone()))
+                    .collect();
+
+                let formatted = format_rules(changed_cfa, &changed_rules);
+                if !formatted.is_empty() {
+                    init.deltas.push(StackCfiRecord {
+                        address,
+                        rules: formatted,
+                    });
+                }
+            }
+        }
+
+        previous_cfa = cfa;
+        previous_rules = rules;
+    }
+
+    Ok(init)
+}
@@ -0,0 +1,210 @@
+use std::cmp;
+
+use gimli::{BaseAddresses, DebugFrame, EhFrame, RunTimeEndian, SectionId};
+use goblin::elf::{header, note, program_header, section_header, Elf};
+
+use demangle::{demangle, DemangleOptions};
+use dwarf;
+use errors::ErrorKind::ObjectError;
+use errors::Result;
+use object;
+use processor::CodeModuleId;
+use symbol_file::{InfoRecord, ModuleRecord, PublicRecord, SymbolFile};
+
+/// Number of bytes of the text section hashed into a module identifier if
+/// the file has no build ID.
+const TEXT_HASH_SIZE: usize = 4096;
+
+/// Generates Breakpad symbols from an ELF file.
+///
+/// FUNC and line records are generated from DWARF debug information, PUBLIC
+/// records from the symbol table and STACK CFI records from `.eh_frame` and
+/// `.debug_frame`. Addresses are relative to the first loadable segment.
+pub(crate) fn dump_symbols(buffer: &[u8], name: &str) -> Result<SymbolFile> {
+    let elf = Elf::parse(buffer)?;
+    let arch = arch_name(elf.header.e_machine)?;
+    let endian = if elf.little_endian {
+        RunTimeEndian::Little
+    } else {
+        RunTimeEndian::Big
+    };
+
+    let load_address = elf.program_headers
+        .iter()
+        .filter(|header| header.p_type == program_header::PT_LOAD)
+        .map(|header| header.p_vaddr)
+        .min()
+        .unwrap_or(0);
+
+    let mut file = SymbolFile::default();
+    if let Some(code_id) = code_id(&elf, buffer) {
+        file.info.push(InfoRecord::CodeId {
+            code_id,
+            code_file: None,
+        });
+    }
+
+    file.module = Some(ModuleRecord {
+        os: "Linux".into(),
+        arch: arch.into(),
+        id: module_id(&elf, buffer)?,
+        name: name.into(),
+    });
+
+    let debug_info = dwarf::read_debug_info(
+        |id| section_data(&elf, buffer, id.name()).unwrap_or(&[]),
+        endian,
+        load_address,
+    )?;
+    file.files = debug_info.files;
+    file.functions = debug_info.functions;
+    file.publics = read_publics(&elf, load_address, &file);
+
+    if let Some(data) = section_data(&elf, buffer, SectionId::EhFrame.name()) {
+        let bases = BaseAddresses::default()
+            .set_eh_frame(section_address(&elf, ".eh_frame").unwrap_or(0))
+            .set_text(section_address(&elf, ".text").unwrap_or(0))
+            .set_got(section_address(&elf, ".got").unwrap_or(0));
+
+        let section = EhFrame::new(data, endian);
+        file.stack_cfi = dwarf::read_cfi(&section, &bases, arch, load_address)?;
+    }
+
+    if let Some(data) = section_data(&elf, buffer, SectionId::DebugFrame.name()) {
+        let section = DebugFrame::new(data, endian);
+        let bases = BaseAddresses::default();
+        let records = dwarf::read_cfi(&section, &bases, arch, load_address)?;
+        file.stack_cfi.extend(records);
+    }
+
+    file.stack_cfi.sort_by_key(|record| record.address);
+    file.stack_cfi.dedup_by_key(|record| record.address);
+
+    Ok(file)
+}
+
+/// Returns Breakpad's name for the machine type of an ELF file.
+fn arch_name(machine: u16) -> Result<&'static str> {
+    Ok(match machine {
+        header::EM_386 => "x86",
+        header::EM_X86_64 => "x86_64",
+        header::EM_ARM => "arm",
+        header::EM_AARCH64 => "arm64",
+        header::EM_MIPS => "mips",
+        header::EM_PPC => "ppc",
+        header::EM_PPC64 => "ppc64",
+        _ => {
+            let message = format!("unsupported machine type {}", machine);
+            return Err(ObjectError(message).into());
+        }
+    })
+}
+
+fn find_section<'a>(elf: &'a Elf, name: &str) -> Option<&'a section_header::SectionHeader> {
+    elf.section_headers.iter().find(|header| {
+        match elf.shdr_strtab.get(header.sh_name) {
+            Some(Ok(section_name)) => section_name == name,
+            _ => false,
+        }
+    })
+}
+
+fn section_address(elf: &Elf, name: &str) -> Option<u64> {
+    find_section(elf, name).map(|header| header.sh_addr)
+}
+
+/// Returns the contents of the section with the given name. Sections that
+/// occupy no space in the file are treated as missing.
+fn section_data<'a>(elf: &Elf, buffer: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    let header = find_section(elf, name)?;
+    if header.sh_type == section_header::SHT_NOBITS {
+        return None;
+    }
+
+    let start = header.sh_offset as usize;
+    let end = start.checked_add(header.sh_size as usize)?;
+    buffer.get(start..end)
+}
+
+/// Reads the GNU build ID from the note segments or sections.
+fn build_id<'a>(elf: &Elf<'a>, buffer: &'a [u8]) -> Option<&'a [u8]> {
+    let notes = elf.iter_note_headers(buffer)
+        .or_else(|| elf.iter_note_sections(buffer, None))?;
+
+    notes
+        .filter_map(|note| note.ok())
+        .find(|note| note.n_type == note::NT_GNU_BUILD_ID)
+        .map(|note| note.desc)
+}
+
+/// Computes a module identifier for files without build ID by XOR-ing the
+/// first page of the text section, like Breakpad's `dump_syms`.
+fn hash_text(elf: &Elf, buffer: &[u8]) -> [u8; 16] {
+    let mut hash = [0u8; 16];
+    if let Some(text) = section_data(elf, buffer, ".text") {
+        let text = &text[..cmp::min(text.len(), TEXT_HASH_SIZE)];
+        for (index, byte) in text.iter().enumerate() {
+            hash[index % 16] ^= byte;
+        }
+    }
+
+    hash
+}
+
+/// Returns the Breakpad identifier of an ELF file.
+///
+/// The identifier is derived from the GNU build ID if present, otherwise from
+/// a hash of the text section. The age is always zero.
+pub(crate) fn module_id(elf: &Elf, buffer: &[u8]) -> Result<CodeModuleId> {
+    match build_id(elf, buffer) {
+        Some(identifier) => object::guid_to_module_id(identifier, 0),
+        None => object::guid_to_module_id(&hash_text(elf, buffer), 0),
+    }
+}
+
+/// Returns the GNU build ID of an ELF file as hex string, if present.
+pub(crate) fn code_id(elf: &Elf, buffer: &[u8]) -> Option<String> {
+    build_id(elf, buffer).map(|identifier| {
+        identifier
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect()
+    })
+}
+
+/// Creates PUBLIC records for all defined functions in the symbol table that
+/// are not covered by a FUNC record.
+fn read_publics(elf: &Elf, load_address: u64, file: &SymbolFile) -> Vec<PublicRecord> {
+    let (symbols, strtab) = if elf.syms.is_empty() {
+        (&elf.dynsyms, &elf.dynstrtab)
+    } else {
+        (&elf.syms, &elf.strtab)
+    };
+
+    let mut publics: Vec<_> = symbols
+        .iter()
+        .filter(|symbol| {
+            symbol.is_function() && symbol.st_shndx != section_header::SHN_UNDEF as usize
+                && symbol.st_value != 0
+        })
+        .filter_map(|symbol| {
+            let address = symbol.st_value.checked_sub(load_address)?;
+            let name = match strtab.get(symbol.st_name) {
+                Some(Ok(name)) if !name.is_empty() => name,
+                _ => return None,
+            };
+
+            Some(PublicRecord {
+                multiple: false,
+                address,
+                parameter_size: 0,
+                name: demangle(name, &DemangleOptions::default()).into_owned(),
+            })
+        })
+        .filter(|public| !dwarf::covers_address(&file.functions, public.address))
+        .collect();
+
+    publics.sort_by_key(|public| public.address);
+    publics.dedup_by_key(|public| public.address);
+    publics
+}
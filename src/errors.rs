@@ -5,6 +5,8 @@ error_chain! {
         IoError(::std::io::Error);
         UuidParseError(::uuid::ParseError);
         ParseIntError(::std::num::ParseIntError);
+        ObjectParseError(::goblin::error::Error);
+        DwarfError(::gimli::Error);
     }
 
     errors {
@@ -20,6 +22,36 @@ error_chain! {
             display("Resolver Error: {}", &desc)
         }
 
+        /// An error raised when parsing a `SymbolFile`.
+        SymbolFileError(desc: String) {
+            description("Symbol File Error")
+            display("Symbol File Error: {}", &desc)
+        }
+
+        /// An error raised when opening a `SymbolIndex`.
+        SymbolIndexError(desc: String) {
+            description("Symbol Index Error")
+            display("Symbol Index Error: {}", &desc)
+        }
+
+        /// An error raised when evaluating a STACK WIN program string.
+        ProgramError(desc: String) {
+            description("Program String Error")
+            display("Program String Error: {}", &desc)
+        }
+
+        /// An error raised when generating symbols from an object file.
+        ObjectError(desc: String) {
+            description("Object File Error")
+            display("Object File Error: {}", &desc)
+        }
+
+        /// An error raised when decompressing symbols or minidumps.
+        DecompressionError(desc: String) {
+            description("Decompression Error")
+            display("Decompression Error: {}", &desc)
+        }
+
         /// An error raised when parsing `CodeModuleId`.
         ParseIdError(desc: String) {
             description("CodeModule ID Parse Error")
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use errors::ErrorKind::ProgramError;
+use errors::Result;
+
+/// An operand on the stack of a `ProgramEvaluator`.
+#[derive(Clone, Copy, Debug)]
+enum Operand<'a> {
+    /// A literal or variable name taken from the program.
+    Token(&'a str),
+    /// The result of an operator.
+    Value(u32),
+}
+
+/// Evaluates postfix program strings of STACK WIN records.
+///
+/// Program strings recover the caller's registers on 32-bit x86. They consist
+/// of whitespace-separated tokens: numbers, variables such as `$eip` or
+/// `.raSearch`, the binary operators `+ - * / %`, `@` to align the first
+/// operand down to a multiple of the second, `^` to read a 32-bit value from
+/// memory and `=` to assign a value to a variable starting with `$`. For
+/// example, `$T0 $ebp = $eip $T0 4 + ^ =` reads the return address above the
+/// saved frame pointer.
+///
+/// The evaluator follows the semantics of Breakpad's stackwalker, so that
+/// broken walks can be reproduced step by step. Before evaluating a STACK WIN
+/// program, the stackwalker defines the registers of the callee frame as well
+/// as `.cbSavedRegs`, `.cbLocals`, `.cbParams`, `.raSearchStart` and
+/// `.raSearch`. All arithmetic wraps around at 32 bits.
+pub struct ProgramEvaluator<M> {
+    variables: BTreeMap<String, u32>,
+    memory: M,
+}
+
+impl<M> ProgramEvaluator<M>
+where
+    M: Fn(u32) -> Option<u32>,
+{
+    /// Creates an evaluator that reads 32-bit values from memory with the
+    /// given function. It returns `None` if an address cannot be read.
+    pub fn new(memory: M) -> ProgramEvaluator<M> {
+        ProgramEvaluator {
+            variables: BTreeMap::new(),
+            memory,
+        }
+    }
+
+    /// Returns the value of a variable, if it has been defined.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.variables.get(name).cloned()
+    }
+
+    /// Defines a variable or changes its value.
+    pub fn set<S: Into<String>>(&mut self, name: S, value: u32) {
+        self.variables.insert(name.into(), value);
+    }
+
+    /// Returns all variables, including the ones assigned by programs.
+    pub fn variables(&self) -> &BTreeMap<String, u32> {
+        &self.variables
+    }
+
+    /// Evaluates a program and returns the names of all assigned variables.
+    ///
+    /// Each variable is listed once, in the order of its first assignment.
+    /// Fails if the program is malformed, uses an undefined variable or reads
+    /// unavailable memory. Assignments up to the failing token persist.
+    pub fn evaluate(&mut self, program: &str) -> Result<Vec<String>> {
+        let mut assigned = Vec::new();
+        let stack = self.evaluate_tokens(program, &mut assigned)?;
+        if !stack.is_empty() {
+            let message = format!("{} unused values in \"{}\"", stack.len(), program);
+            return Err(ProgramError(message).into());
+        }
+
+        Ok(assigned)
+    }
+
+    /// Evaluates an expression that leaves a single value, such as the
+    /// `.cfa` rule of a frame, and returns that value.
+    pub fn evaluate_value(&mut self, expression: &str) -> Result<u32> {
+        let mut assigned = Vec::new();
+        let mut stack = self.evaluate_tokens(expression, &mut assigned)?;
+        if stack.len() != 1 {
+            let message = format!("expected a single value in \"{}\"", expression);
+            return Err(ProgramError(message).into());
+        }
+
+        self.resolve(stack.remove(0))
+    }
+
+    fn evaluate_tokens<'a>(
+        &mut self,
+        program: &'a str,
+        assigned: &mut Vec<String>,
+    ) -> Result<Vec<Operand<'a>>> {
+        let mut stack = Vec::new();
+        for token in program.split_whitespace() {
+            // Programs written by MSVC 2010 with link-time code generation
+            // occasionally omit the space after an assignment, as in
+            // "$ebp $T0 ^ =$esp".
+            if token.len() > 1 && token.starts_with('=') {
+                self.evaluate_token("=", &mut stack, assigned)?;
+                self.evaluate_token(&token[1..], &mut stack, assigned)?;
+            } else {
+                self.evaluate_token(token, &mut stack, assigned)?;
+            }
+        }
+
+        Ok(stack)
+    }
+
+    fn evaluate_token<'a>(
+        &mut self,
+        token: &'a str,
+        stack: &mut Vec<Operand<'a>>,
+        assigned: &mut Vec<String>,
+    ) -> Result<()> {
+        match token {
+            "+" | "-" | "*" | "/" | "%" | "@" => {
+                let right = self.pop_value(stack, token)?;
+                let left = self.pop_value(stack, token)?;
+                let value = match token {
+                    "+" => left.wrapping_add(right),
+                    "-" => left.wrapping_sub(right),
+                    "*" => left.wrapping_mul(right),
+                    "/" | "%" if right == 0 => {
+                        let message = format!("division by zero in \"{} 0 {}\"", left, token);
+                        return Err(ProgramError(message).into());
+                    }
+                    "/" => left / right,
+                    "%" => left % right,
+                    _ => left & right.wrapping_neg(),
+                };
+                stack.push(Operand::Value(value));
+            }
+            "^" => {
+                let address = self.pop_value(stack, token)?;
+                let value = (self.memory)(address).ok_or_else(|| {
+                    ProgramError(format!("cannot read memory at {:#x}", address))
+                })?;
+                stack.push(Operand::Value(value));
+            }
+            "=" => {
+                let value = self.pop_value(stack, token)?;
+                let name = match stack.pop() {
+                    Some(Operand::Token(name)) if name.starts_with('$') => name,
+                    Some(Operand::Token(name)) => {
+                        let message = format!("cannot assign to \"{}\"", name);
+                        return Err(ProgramError(message).into());
+                    }
+                    _ => {
+                        let message = "missing variable for assignment".to_string();
+                        return Err(ProgramError(message).into());
+                    }
+                };
+
+                if !assigned.iter().any(|existing| existing == name) {
+                    assigned.push(name.to_string());
+                }
+                self.variables.insert(name.to_string(), value);
+            }
+            _ => stack.push(Operand::Token(token)),
+        }
+
+        Ok(())
+    }
+
+    fn pop_value(&self, stack: &mut Vec<Operand>, operator: &str) -> Result<u32> {
+        match stack.pop() {
+            Some(operand) => self.resolve(operand),
+            None => {
+                let message = format!("missing operand for \"{}\"", operator);
+                Err(ProgramError(message).into())
+            }
+        }
+    }
+
+    fn resolve(&self, operand: Operand) -> Result<u32> {
+        let token = match operand {
+            Operand::Token(token) => token,
+            Operand::Value(value) => return Ok(value),
+        };
+
+        if let Some(value) = parse_literal(token) {
+            return Ok(value);
+        }
+
+        self.get(token).ok_or_else(|| {
+            ProgramError(format!("undefined variable \"{}\"", token)).into()
+        })
+    }
+}
+
+/// Parses a decimal or hexadecimal number. Negative numbers wrap around.
+fn parse_literal(token: &str) -> Option<u32> {
+    let (negative, digits) = match token.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, token),
+    };
+
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+
+    Some(if negative { value.wrapping_neg() } else { value })
+}
@@ -1,12 +1,41 @@
 #[macro_use]
 extern crate error_chain;
+extern crate cpp_demangle;
+#[cfg(any(feature = "gzip", feature = "zlib"))]
+extern crate flate2;
+extern crate gimli;
+extern crate goblin;
+extern crate rustc_demangle;
 extern crate uuid;
+#[cfg(feature = "zstd")]
+extern crate zstd;
 
+mod compact_unwind;
+mod demangle;
+mod dwarf;
+mod elf;
 mod errors;
+mod evaluator;
+mod mach;
+mod merge;
+mod object;
 mod processor;
 mod resolver;
+mod symbol_file;
+mod symbol_index;
+mod symbolicate;
+mod symbols;
 mod utils;
 
+pub use demangle::*;
 pub use errors::*;
+pub use evaluator::*;
+pub use merge::*;
+pub use object::*;
 pub use processor::*;
 pub use resolver::*;
+pub use symbol_file::*;
+pub use symbol_index::*;
+pub use symbolicate::*;
+pub use symbols::*;
+pub use utils::decompress;
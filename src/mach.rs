@@ -0,0 +1,255 @@
+use gimli::{BaseAddresses, DebugFrame, EhFrame, RunTimeEndian, SectionId};
+use goblin::mach::constants::{S_ATTR_PURE_INSTRUCTIONS, S_ATTR_SOME_INSTRUCTIONS};
+use goblin::mach::cputype;
+use goblin::mach::load_command::CommandVariant;
+use goblin::mach::symbols::{N_SECT, N_STAB, N_TYPE};
+use goblin::mach::{Mach, MachO};
+use uuid::Uuid;
+
+use compact_unwind;
+use demangle::{demangle, DemangleOptions};
+use dwarf;
+use errors::ErrorKind::ObjectError;
+use errors::Result;
+use processor::CodeModuleId;
+use symbol_file::{ModuleRecord, PublicRecord, SymbolFile};
+
+/// Maximum length of section names in Mach-O files.
+const SECTION_NAME_LENGTH: usize = 16;
+
+/// Generates Breakpad symbols from a Mach-O file, optionally with debug
+/// information from a separate dSYM file.
+///
+/// Both files may be universal binaries. If they contain more than one
+/// architecture, `arch` selects the one to dump. FUNC and line records are
+/// read from the DWARF sections of `debug_buffer` if given, otherwise from
+/// `buffer`. PUBLIC records are read from the symbol table and STACK CFI
+/// records from `__eh_frame`, `__debug_frame` and `__unwind_info` in
+/// `buffer`. Addresses are relative to the `__TEXT` segment.
+pub(crate) fn dump_symbols(
+    buffer: &[u8],
+    debug_buffer: Option<&[u8]>,
+    name: &str,
+    arch: Option<&str>,
+) -> Result<SymbolFile> {
+    let macho = select_arch(buffer, arch)?;
+    let arch = arch_name(macho.header.cputype, macho.header.cpusubtype)?;
+    let uuid = module_uuid(&macho)?;
+
+    let debug_macho = match debug_buffer {
+        Some(debug_buffer) => {
+            let debug_macho = select_arch(debug_buffer, Some(arch))?;
+            if module_uuid(&debug_macho)? != uuid {
+                return Err(ObjectError("debug file does not match the binary".into()).into());
+            }
+            Some(debug_macho)
+        }
+        None => None,
+    };
+
+    let endian = if macho.little_endian {
+        RunTimeEndian::Little
+    } else {
+        RunTimeEndian::Big
+    };
+
+    let load_address = macho
+        .segments
+        .iter()
+        .find(|segment| segment.name().ok() == Some("__TEXT"))
+        .map_or(0, |segment| segment.vmaddr);
+
+    let debug_info = {
+        let debug = debug_macho.as_ref().unwrap_or(&macho);
+        dwarf::read_debug_info(
+            |id| {
+                section(debug, "__DWARF", &dwarf_section_name(id))
+                    .map_or(&[], |(_, data)| data)
+            },
+            endian,
+            load_address,
+        )?
+    };
+
+    let mut file = SymbolFile {
+        module: Some(ModuleRecord {
+            os: "mac".into(),
+            arch: arch.into(),
+            id: CodeModuleId::from_parts(uuid, 0),
+            name: name.into(),
+        }),
+        files: debug_info.files,
+        functions: debug_info.functions,
+        ..Default::default()
+    };
+    file.publics = read_publics(&macho, load_address, &file)?;
+
+    if let Some((address, data)) = section(&macho, "__TEXT", "__eh_frame") {
+        let text_address = section(&macho, "__TEXT", "__text").map_or(0, |(address, _)| address);
+        let bases = BaseAddresses::default()
+            .set_eh_frame(address)
+            .set_text(text_address);
+
+        let section = EhFrame::new(data, endian);
+        file.stack_cfi = dwarf::read_cfi(&section, &bases, arch, load_address)?;
+    }
+
+    if let Some((_, data)) = section(&macho, "__DWARF", "__debug_frame") {
+        let section = DebugFrame::new(data, endian);
+        let bases = BaseAddresses::default();
+        let records = dwarf::read_cfi(&section, &bases, arch, load_address)?;
+        file.stack_cfi.extend(records);
+    }
+
+    // Compact unwind information is less precise than DWARF CFI, since it
+    // only describes the function body. Sorting is stable, so records from
+    // DWARF take precedence when removing duplicates.
+    if let Some((_, data)) = section(&macho, "__TEXT", "__unwind_info") {
+        let records = compact_unwind::read_compact_unwind(data, arch)?;
+        file.stack_cfi.extend(records);
+    }
+
+    file.stack_cfi.sort_by_key(|record| record.address);
+    file.stack_cfi.dedup_by_key(|record| record.address);
+
+    Ok(file)
+}
+
+/// Returns the Mach-O file for the given architecture.
+///
+/// Without an architecture, the file must contain exactly one.
+pub(crate) fn select_arch<'a>(buffer: &'a [u8], arch: Option<&str>) -> Result<MachO<'a>> {
+    let mut candidates = match Mach::parse(buffer)? {
+        Mach::Binary(macho) => vec![macho],
+        Mach::Fat(fat) => {
+            let mut candidates = Vec::new();
+            for index in 0..fat.narches {
+                candidates.push(fat.get(index)?);
+            }
+            candidates
+        }
+    };
+
+    if let Some(arch) = arch {
+        candidates.retain(|macho| {
+            arch_name(macho.header.cputype, macho.header.cpusubtype).ok() == Some(arch)
+        });
+    }
+
+    match candidates.len() {
+        1 => Ok(candidates.pop().unwrap()),
+        0 => Err(ObjectError(format!("architecture {} not found", arch.unwrap_or(""))).into()),
+        _ => Err(ObjectError("multiple architectures, select one".into()).into()),
+    }
+}
+
+/// Returns Breakpad's name for the CPU type of a Mach-O file.
+fn arch_name(cputype: u32, cpusubtype: u32) -> Result<&'static str> {
+    Ok(match (cputype, cpusubtype & !cputype::CPU_SUBTYPE_MASK) {
+        (cputype::CPU_TYPE_X86, _) => "x86",
+        (cputype::CPU_TYPE_X86_64, _) => "x86_64",
+        (cputype::CPU_TYPE_ARM, cputype::CPU_SUBTYPE_ARM_V6) => "armv6",
+        (cputype::CPU_TYPE_ARM, cputype::CPU_SUBTYPE_ARM_V7) => "armv7",
+        (cputype::CPU_TYPE_ARM, cputype::CPU_SUBTYPE_ARM_V7S) => "armv7s",
+        (cputype::CPU_TYPE_ARM, cputype::CPU_SUBTYPE_ARM_V7K) => "armv7k",
+        (cputype::CPU_TYPE_ARM, _) => "arm",
+        (cputype::CPU_TYPE_ARM64, _) => "arm64",
+        (cputype::CPU_TYPE_POWERPC, _) => "ppc",
+        (cputype::CPU_TYPE_POWERPC64, _) => "ppc64",
+        _ => {
+            let message = format!("unsupported cpu type {}", cputype);
+            return Err(ObjectError(message).into());
+        }
+    })
+}
+
+/// Reads the UUID from the `LC_UUID` load command.
+pub(crate) fn module_uuid(macho: &MachO) -> Result<Uuid> {
+    for command in &macho.load_commands {
+        if let CommandVariant::Uuid(ref uuid) = command.command {
+            return Ok(Uuid::from_bytes(&uuid.uuid)?);
+        }
+    }
+
+    Err(ObjectError("missing LC_UUID load command".into()).into())
+}
+
+/// Converts the name of a DWARF section to its Mach-O equivalent, such as
+/// `.debug_info` to `__debug_info`.
+fn dwarf_section_name(id: SectionId) -> String {
+    let mut name = format!("__{}", &id.name()[1..]);
+    name.truncate(SECTION_NAME_LENGTH);
+    name
+}
+
+/// Returns the address and contents of a section. Sections without contents
+/// in this file, such as code sections in a dSYM, are treated as missing.
+fn section<'a>(macho: &MachO<'a>, segment_name: &str, name: &str) -> Option<(u64, &'a [u8])> {
+    for segment in macho.segments.iter() {
+        if segment.name().ok() != Some(segment_name) {
+            continue;
+        }
+
+        for (section, data) in segment.sections().ok()? {
+            if section.name().ok() == Some(name) && section.offset != 0 && !data.is_empty() {
+                return Some((section.addr, data));
+            }
+        }
+    }
+
+    None
+}
+
+/// Creates PUBLIC records for all defined symbols in code sections that are
+/// not covered by a FUNC record.
+fn read_publics(macho: &MachO, load_address: u64, file: &SymbolFile) -> Result<Vec<PublicRecord>> {
+    let mut code_ranges = Vec::new();
+    for segment in macho.segments.iter() {
+        for (section, _) in segment.sections()? {
+            if section.flags & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS) != 0 {
+                let end = section.addr.saturating_add(section.size);
+                code_ranges.push((section.addr, end));
+            }
+        }
+    }
+
+    let mut publics = Vec::new();
+    for symbol in macho.symbols() {
+        let (name, nlist) = symbol?;
+        if nlist.n_type & N_STAB != 0 || nlist.n_type & N_TYPE != N_SECT {
+            continue;
+        }
+
+        // Skip symbols outside of code, such as the Mach-O header.
+        let in_code = code_ranges
+            .iter()
+            .any(|&(start, end)| nlist.n_value >= start && nlist.n_value < end);
+        if !in_code {
+            continue;
+        }
+
+        let address = match nlist.n_value.checked_sub(load_address) {
+            Some(address) => address,
+            None => continue,
+        };
+
+        let covered = dwarf::covers_address(&file.functions, address);
+
+        // C symbols carry an additional leading underscore on Apple platforms.
+        let name = name.strip_prefix('_').unwrap_or(name);
+        if covered || name.is_empty() {
+            continue;
+        }
+
+        publics.push(PublicRecord {
+            multiple: false,
+            address,
+            parameter_size: 0,
+            name: demangle(name, &DemangleOptions::default()).into_owned(),
+        });
+    }
+
+    publics.sort_by_key(|public| public.address);
+    publics.dedup_by_key(|public| public.address);
+    Ok(publics)
+}
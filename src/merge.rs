@@ -0,0 +1,468 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+use errors::ErrorKind::SymbolFileError;
+use errors::Result;
+use symbol_file::{FileRecord, FuncRecord, InfoRecord, InlineOriginRecord, RecordType,
+                  SymbolFile};
+
+/// Decides which symbol file wins if records of several files overlap.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Precedence {
+    /// Records of earlier files replace records of later files.
+    First,
+    /// Records of later files replace records of earlier files.
+    Last,
+}
+
+/// Options for merging symbol files with `SymbolFile::merge`.
+///
+/// Precedence can be configured separately for each kind of record, so that
+/// for instance functions are taken from one file and unwind information from
+/// another. By default, earlier files take precedence for all records.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct MergeOptions {
+    /// Precedence of FUNC records along with their line and INLINE records.
+    pub functions: Precedence,
+    /// Precedence of PUBLIC records.
+    pub publics: Precedence,
+    /// Precedence of STACK CFI and STACK WIN records.
+    pub stack: Precedence,
+}
+
+impl Default for MergeOptions {
+    fn default() -> MergeOptions {
+        MergeOptions {
+            functions: Precedence::First,
+            publics: Precedence::First,
+            stack: Precedence::First,
+        }
+    }
+}
+
+/// A record that was dropped while merging symbol files, because it overlaps
+/// with a different record of a file with higher precedence.
+///
+/// Identical records in several files are merged silently.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MergeConflict {
+    /// The type of the dropped record.
+    pub record_type: RecordType,
+    /// Start address of the dropped record.
+    pub address: u64,
+    /// Index of the file whose record was kept.
+    pub kept: usize,
+    /// Index of the file whose record was dropped.
+    pub dropped: usize,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} record at {:#x} of file {} conflicts with file {}",
+            self.record_type, self.address, self.dropped, self.kept
+        )
+    }
+}
+
+/// The result of `SymbolFile::merge`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MergedSymbolFile {
+    /// The merged symbol file.
+    pub file: SymbolFile,
+    /// All records that were dropped in favor of overlapping records.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Address ranges of merged records, mapping a namespace and start address
+/// to the end address and the index of the merged record.
+///
+/// Records of the same file may overlap or nest, so every entry also stores
+/// the highest end address of all entries in its namespace up to and
+/// including itself.
+#[derive(Default)]
+struct Ranges(BTreeMap<(u32, u64), RangeEntry>);
+
+struct RangeEntry {
+    end: u64,
+    index: usize,
+    reach: u64,
+}
+
+impl Ranges {
+    /// Returns the index of a merged record overlapping the given range.
+    fn find(&self, space: u32, start: u64, end: u64) -> Option<usize> {
+        if let Some((_, entry)) = self.0.range((space, start)..(space, end)).next() {
+            return Some(entry.index);
+        }
+
+        // Walk back through all earlier ranges that may still reach past the
+        // start, since a nested range can hide an enclosing one.
+        self.0
+            .range((space, 0)..(space, start))
+            .rev()
+            .take_while(|&(_, entry)| entry.reach > start)
+            .find(|&(_, entry)| entry.end > start)
+            .map(|(_, entry)| entry.index)
+    }
+
+    fn insert(&mut self, space: u32, start: u64, end: u64, index: usize) {
+        let entry = self.0.entry((space, start)).or_insert(RangeEntry {
+            end,
+            index,
+            reach: end,
+        });
+
+        if end > entry.end {
+            entry.end = end;
+            entry.index = index;
+        }
+    }
+
+    /// Recomputes the highest end address of every entry after inserting.
+    fn update_reach(&mut self) {
+        let mut current = None;
+        for (&(space, _), entry) in &mut self.0 {
+            let reach = match current {
+                Some((previous_space, reach)) if previous_space == space => entry.end.max(reach),
+                _ => entry.end,
+            };
+
+            entry.reach = reach;
+            current = Some((space, reach));
+        }
+    }
+}
+
+/// Merges the records of one kind from all files.
+///
+/// `range` returns the namespace, start and end address of a record. Records
+/// of a file are skipped if they overlap a record from a file with higher
+/// precedence. They are reported as conflicts, unless `same` considers both
+/// records equal. Returns the merged records along with the index of the
+/// file each record originates from.
+fn merge_records<T, R, S>(
+    files: Vec<Vec<T>>,
+    precedence: Precedence,
+    record_type: RecordType,
+    range: R,
+    same: S,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Vec<(usize, T)>
+where
+    R: Fn(&T) -> (u32, u64, u64),
+    S: Fn(&T, &T) -> bool,
+{
+    let mut files: Vec<_> = files.into_iter().enumerate().collect();
+    if precedence == Precedence::Last {
+        files.reverse();
+    }
+
+    let mut merged: Vec<(usize, T)> = Vec::new();
+    let mut ranges = Ranges::default();
+    for (file_index, records) in files {
+        let first_new = merged.len();
+        for record in records {
+            let (space, start, end) = range(&record);
+            if let Some(index) = ranges.find(space, start, end) {
+                let (kept, ref existing) = merged[index];
+                if !same(existing, &record) {
+                    conflicts.push(MergeConflict {
+                        record_type,
+                        address: start,
+                        kept,
+                        dropped: file_index,
+                    });
+                }
+                continue;
+            }
+
+            merged.push((file_index, record));
+        }
+
+        // Records of the same file may overlap, so they are only checked
+        // against files with higher precedence.
+        for (index, entry) in merged.iter().enumerate().skip(first_new) {
+            let (space, start, end) = range(&entry.1);
+            ranges.insert(space, start, end, index);
+        }
+        ranges.update_reach();
+    }
+
+    merged
+}
+
+/// Returns the end of an address range. Empty ranges are extended to one
+/// byte, so that they can still conflict with other records.
+fn range_end(address: u64, size: u64) -> u64 {
+    address.saturating_add(size.max(1))
+}
+
+/// Name of the FILE and INLINE_ORIGIN records that replace references to
+/// records missing in the source file.
+const UNKNOWN_NAME: &str = "<unknown>";
+
+/// Assigns new identifiers to FILE and INLINE_ORIGIN records of merged
+/// functions, deduplicating them by key.
+struct IdTable<K> {
+    ids: HashMap<K, u32>,
+    /// Keys in the order of their identifiers. `None` marks the placeholder
+    /// for missing records.
+    keys: Vec<Option<K>>,
+    placeholder: Option<u32>,
+}
+
+impl<K: Clone + Eq + Hash> IdTable<K> {
+    fn new() -> IdTable<K> {
+        IdTable {
+            ids: HashMap::new(),
+            keys: Vec::new(),
+            placeholder: None,
+        }
+    }
+
+    fn map(&mut self, key: &K) -> u32 {
+        if let Some(&id) = self.ids.get(key) {
+            return id;
+        }
+
+        let id = self.keys.len() as u32;
+        self.ids.insert(key.clone(), id);
+        self.keys.push(Some(key.clone()));
+        id
+    }
+
+    /// Returns the identifier of a placeholder record, which never collides
+    /// with a record of the same name.
+    fn placeholder(&mut self) -> u32 {
+        if let Some(id) = self.placeholder {
+            return id;
+        }
+
+        let id = self.keys.len() as u32;
+        self.keys.push(None);
+        self.placeholder = Some(id);
+        id
+    }
+}
+
+/// Identifiers of FILE and INLINE_ORIGIN records of a single source file.
+struct SourceIds<'a> {
+    files: HashMap<u32, &'a str>,
+    origins: HashMap<u32, &'a str>,
+    /// Whether the file has legacy INLINE records without a call site file.
+    /// INLINE_ORIGIN names then start with a file identifier.
+    legacy: bool,
+}
+
+impl<'a> SourceIds<'a> {
+    fn new(file: &'a SymbolFile) -> SourceIds<'a> {
+        SourceIds {
+            files: file.files
+                .iter()
+                .map(|record| (record.id, record.name.as_str()))
+                .collect(),
+            origins: file.inline_origins
+                .iter()
+                .map(|record| (record.id, record.name.as_str()))
+                .collect(),
+            legacy: file.functions
+                .iter()
+                .flat_map(|function| &function.inlines)
+                .any(|inline| inline.call_file.is_none()),
+        }
+    }
+}
+
+/// Identifiers of FILE and INLINE_ORIGIN records in the merged file.
+///
+/// Inline origins are keyed by the merged identifier of their file, which is
+/// only known for origins of files with legacy INLINE records, and their name.
+struct MergedIds {
+    files: IdTable<String>,
+    origins: IdTable<(Option<u32>, String)>,
+}
+
+impl MergedIds {
+    fn file(&mut self, source: &SourceIds, id: u32) -> u32 {
+        match source.files.get(&id) {
+            Some(name) => self.files.map(&name.to_string()),
+            None => self.files.placeholder(),
+        }
+    }
+
+    fn origin(&mut self, source: &SourceIds, id: u32) -> u32 {
+        let name = match source.origins.get(&id) {
+            Some(name) => *name,
+            None => return self.origins.placeholder(),
+        };
+
+        if !source.legacy {
+            return self.origins.map(&(None, name.to_string()));
+        }
+
+        // INLINE_ORIGIN <id> <file> <name>
+        let mut parts = name.splitn(2, ' ');
+        let file = parts.next().and_then(|file| file.parse().ok());
+        let key = match (file, parts.next()) {
+            (Some(file), Some(name)) => (Some(self.file(source, file)), name.to_string()),
+            _ => (Some(self.files.placeholder()), name.to_string()),
+        };
+
+        self.origins.map(&key)
+    }
+
+    /// Creates the FILE and INLINE_ORIGIN records of the merged file.
+    ///
+    /// If `legacy` is set, the merged file contains legacy INLINE records
+    /// and all INLINE_ORIGIN names must start with a file identifier.
+    fn into_records(mut self, legacy: bool) -> (Vec<FileRecord>, Vec<InlineOriginRecord>) {
+        let mut origins = Vec::with_capacity(self.origins.keys.len());
+        for (id, key) in self.origins.keys.into_iter().enumerate() {
+            let (file, name) = key.unwrap_or((None, UNKNOWN_NAME.to_string()));
+            let name = match file {
+                _ if !legacy => name,
+                Some(file) => format!("{} {}", file, name),
+                None => format!("{} {}", self.files.placeholder(), name),
+            };
+
+            origins.push(InlineOriginRecord { id: id as u32, name });
+        }
+
+        let files = self.files
+            .keys
+            .into_iter()
+            .enumerate()
+            .map(|(id, name)| FileRecord {
+                id: id as u32,
+                name: name.unwrap_or_else(|| UNKNOWN_NAME.to_string()),
+            })
+            .collect();
+
+        (files, origins)
+    }
+}
+
+pub(crate) fn merge(files: &[SymbolFile], options: &MergeOptions) -> Result<MergedSymbolFile> {
+    let mut merged = MergedSymbolFile::default();
+
+    for file in files {
+        let module = match file.module {
+            Some(ref module) => module,
+            None => continue,
+        };
+
+        match merged.file.module {
+            Some(ref existing) if existing.id != module.id => {
+                let message = format!(
+                    "cannot merge symbols of modules {} and {}",
+                    existing.id, module.id
+                );
+                return Err(SymbolFileError(message).into());
+            }
+            Some(_) => (),
+            None => merged.file.module = Some(module.clone()),
+        }
+    }
+
+    for info in files.iter().flat_map(|file| &file.info) {
+        let duplicate = merged.file.info.iter().any(|existing| match (existing, info) {
+            (&InfoRecord::CodeId { .. }, &InfoRecord::CodeId { .. }) => true,
+            _ => existing == info,
+        });
+
+        if !duplicate {
+            merged.file.info.push(info.clone());
+        }
+    }
+
+    let functions = merge_records(
+        files.iter().map(|file| file.functions.clone()).collect(),
+        options.functions,
+        RecordType::Func,
+        |f| (0, f.address, range_end(f.address, f.size)),
+        |a, b| a.address == b.address && a.size == b.size && a.name == b.name,
+        &mut merged.conflicts,
+    );
+
+    let sources: Vec<_> = files.iter().map(SourceIds::new).collect();
+    let mut ids = MergedIds {
+        files: IdTable::new(),
+        origins: IdTable::new(),
+    };
+
+    for (file_index, function) in functions {
+        let function = remap_function(function, &sources[file_index], &mut ids);
+        merged.file.functions.push(function);
+    }
+
+    let legacy = merged
+        .file
+        .functions
+        .iter()
+        .flat_map(|function| &function.inlines)
+        .any(|inline| inline.call_file.is_none());
+    let (file_records, origin_records) = ids.into_records(legacy);
+    merged.file.files = file_records;
+    merged.file.inline_origins = origin_records;
+
+    let publics = merge_records(
+        files.iter().map(|file| file.publics.clone()).collect(),
+        options.publics,
+        RecordType::Public,
+        |p| (0, p.address, p.address.saturating_add(1)),
+        |a, b| a.name == b.name,
+        &mut merged.conflicts,
+    );
+    merged.file.publics = publics.into_iter().map(|(_, public)| public).collect();
+
+    let stack_cfi = merge_records(
+        files.iter().map(|file| file.stack_cfi.clone()).collect(),
+        options.stack,
+        RecordType::Stack,
+        |c| (0, c.address, range_end(c.address, c.size)),
+        |a, b| a == b,
+        &mut merged.conflicts,
+    );
+    merged.file.stack_cfi = stack_cfi.into_iter().map(|(_, cfi)| cfi).collect();
+
+    // STACK WIN records of different types describe the same code, so they
+    // only conflict with records of the same type.
+    let stack_win = merge_records(
+        files.iter().map(|file| file.stack_win.clone()).collect(),
+        options.stack,
+        RecordType::Stack,
+        |w| (w.frame_type.to_raw(), w.address, range_end(w.address, w.size)),
+        |a, b| a == b,
+        &mut merged.conflicts,
+    );
+    merged.file.stack_win = stack_win.into_iter().map(|(_, win)| win).collect();
+
+    merged.file.functions.sort_by_key(|function| function.address);
+    merged.file.publics.sort_by_key(|public| public.address);
+    merged.file.stack_cfi.sort_by_key(|cfi| cfi.address);
+    merged.file.stack_win.sort_by_key(|win| win.address);
+    merged
+        .conflicts
+        .sort_by_key(|conflict| (conflict.address, conflict.dropped));
+
+    Ok(merged)
+}
+
+/// Replaces the FILE and INLINE_ORIGIN identifiers of a function with the
+/// identifiers of the merged file. References to records that do not exist
+/// in the source file point to placeholder records named `<unknown>`.
+fn remap_function(mut function: FuncRecord, source: &SourceIds, ids: &mut MergedIds) -> FuncRecord {
+    for line in &mut function.lines {
+        line.file_id = ids.file(source, line.file_id);
+    }
+
+    for inline in &mut function.inlines {
+        inline.call_file = inline.call_file.map(|id| ids.file(source, id));
+        inline.origin_id = ids.origin(source, inline.origin_id);
+    }
+
+    function
+}
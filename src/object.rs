@@ -0,0 +1,79 @@
+use std::cmp;
+
+use goblin::pe::PE;
+use goblin::Object;
+use uuid::Uuid;
+
+use elf;
+use errors::ErrorKind::ObjectError;
+use errors::Result;
+use mach;
+use processor::CodeModuleId;
+
+/// Reads the Breakpad identifier of an ELF, Mach-O or PE file.
+///
+/// Universal Mach-O binaries must contain exactly one architecture.
+pub(crate) fn module_id(buffer: &[u8]) -> Result<CodeModuleId> {
+    match Object::parse(buffer)? {
+        Object::Elf(ref elf) => elf::module_id(elf, buffer),
+        Object::Mach(_) => {
+            let macho = mach::select_arch(buffer, None)?;
+            Ok(CodeModuleId::from_parts(mach::module_uuid(&macho)?, 0))
+        }
+        Object::PE(ref pe) => pe_module_id(pe),
+        _ => Err(ObjectError("unsupported object file".into()).into()),
+    }
+}
+
+/// Reads the code identifier of an ELF, Mach-O or PE file.
+///
+/// This is the value of `CodeModule::code_identifier` for the same file in a
+/// minidump: the hex encoded GNU build ID for ELF, the `LC_UUID` for Mach-O
+/// and the timestamp followed by the image size for PE. Returns `None` for
+/// ELF files without build ID.
+pub fn object_code_identifier(buffer: &[u8]) -> Result<Option<String>> {
+    match Object::parse(buffer)? {
+        Object::Elf(ref elf) => Ok(elf::code_id(elf, buffer)),
+        Object::Mach(_) => {
+            let macho = mach::select_arch(buffer, None)?;
+            let uuid = mach::module_uuid(&macho)?;
+            Ok(Some(uuid.simple().to_string().to_uppercase()))
+        }
+        Object::PE(ref pe) => {
+            let image_size = pe.header
+                .optional_header
+                .as_ref()
+                .map_or(0, |header| header.windows_fields.size_of_image);
+            let timestamp = pe.header.coff_header.time_date_stamp;
+            Ok(Some(format!("{:08X}{:x}", timestamp, image_size)))
+        }
+        _ => Err(ObjectError("unsupported object file".into()).into()),
+    }
+}
+
+/// Reads the GUID and age of the PDB from the CodeView debug record.
+fn pe_module_id(pe: &PE) -> Result<CodeModuleId> {
+    let codeview = pe.debug_data
+        .as_ref()
+        .and_then(|debug_data| debug_data.codeview_pdb70_debug_info.as_ref());
+
+    match codeview {
+        Some(codeview) => guid_to_module_id(&codeview.signature, codeview.age),
+        None => Err(ObjectError("missing CodeView debug record".into()).into()),
+    }
+}
+
+/// Converts the first 16 bytes of an identifier into a `CodeModuleId`.
+///
+/// Breakpad interprets these bytes as a little-endian GUID, so the first
+/// three fields are byte-swapped. Shorter identifiers are padded with zeros.
+pub(crate) fn guid_to_module_id(identifier: &[u8], age: u32) -> Result<CodeModuleId> {
+    let mut data = [0u8; 16];
+    let len = cmp::min(identifier.len(), data.len());
+    data[..len].copy_from_slice(&identifier[..len]);
+    data[0..4].reverse();
+    data[4..6].reverse();
+    data[6..8].reverse();
+
+    Ok(CodeModuleId::from_parts(Uuid::from_bytes(&data)?, age))
+}
@@ -1,15 +1,17 @@
-use std::{fmt, mem, ptr, slice};
+use std::{fmt, mem, slice};
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::collections::HashSet;
-use std::ffi::CString;
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
 use std::hash::{Hash, Hasher};
-use std::os::raw::{c_char, c_void};
+use std::os::raw::{c_char, c_int, c_void};
 use std::path::Path;
 use uuid::Uuid;
 
 use errors::ErrorKind::{ParseIdError, ProcessError};
 use errors::Result;
+use object;
 use utils;
 
 extern "C" {
@@ -21,8 +23,15 @@ extern "C" {
     fn code_module_debug_identifier(module: *const CodeModule) -> *mut c_char;
 
     fn stack_frame_instruction(frame: *const StackFrame) -> u64;
+    fn stack_frame_return_address(frame: *const StackFrame) -> u64;
+    fn stack_frame_cpu_family(frame: *const StackFrame) -> CpuFamily;
     fn stack_frame_module(frame: *const StackFrame) -> *const CodeModule;
     fn stack_frame_trust(frame: *const StackFrame) -> FrameTrust;
+    fn stack_frame_function_name(frame: *const StackFrame) -> *const c_char;
+    fn stack_frame_source_file_name(frame: *const StackFrame) -> *const c_char;
+    fn stack_frame_source_line(frame: *const StackFrame) -> c_int;
+    fn stack_frame_function_base(frame: *const StackFrame) -> u64;
+    fn stack_frame_source_line_base(frame: *const StackFrame) -> u64;
 
     fn call_stack_thread_id(stack: *const CallStack) -> u32;
     fn call_stack_frames(stack: *const CallStack, size_out: *mut usize)
@@ -35,11 +44,38 @@ extern "C" {
         symbol_count: usize,
         result: *mut ProcessResult,
     ) -> *mut IProcessState;
+    fn processor_new() -> *mut IProcessor;
+    fn processor_delete(processor: *mut IProcessor);
+    fn processor_process(
+        processor: *mut IProcessor,
+        buffer: *const c_char,
+        buffer_size: usize,
+        symbols: *const SymbolEntry,
+        symbol_count: usize,
+        result: *mut ProcessResult,
+    ) -> *mut IProcessState;
+    fn processor_evict(processor: *mut IProcessor, debug_identifier: *const c_char) -> bool;
+    fn processor_clear(processor: *mut IProcessor);
+    fn processor_set_trace(processor: *mut IProcessor, trace: bool);
+
+    fn process_state_rewalk(
+        state: *mut IProcessState,
+        thread_indexes: *const usize,
+        thread_count: usize,
+        symbols: *const SymbolEntry,
+        symbol_count: usize,
+    ) -> bool;
     fn process_state_delete(state: *mut IProcessState);
     fn process_state_threads(
         state: *const IProcessState,
         size_out: *mut usize,
     ) -> *const *const CallStack;
+    fn process_state_trace(
+        state: *const IProcessState,
+        thread_index: usize,
+        size_out: *mut usize,
+        termination_out: *mut WalkTermination,
+    ) -> *const FrameTraceEntry;
 }
 
 /// Unique identifier of a `CodeModule`
@@ -50,9 +86,15 @@ pub struct CodeModuleId {
 }
 
 impl CodeModuleId {
-    /// Parses a CodeModuleId from a 33 character `String`
+    /// Parses a CodeModuleId from a `String` containing a 32 character UUID
+    /// followed by the hexadecimal age. The age usually consists of a single
+    /// character, but can be up to eight characters long on Windows.
     pub fn parse(input: &str) -> Result<CodeModuleId> {
-        if input.len() != 33 {
+        if !input.is_ascii() {
+            return Err(ParseIdError("Invalid characters in input string".into()).into());
+        }
+
+        if input.len() < 33 || input.len() > 40 {
             return Err(ParseIdError("Invalid input string length".into()).into());
         }
 
@@ -61,6 +103,20 @@ impl CodeModuleId {
         Ok(CodeModuleId { uuid, age })
     }
 
+    /// Creates a CodeModuleId from a UUID and an age.
+    pub fn from_parts(uuid: Uuid, age: u32) -> CodeModuleId {
+        CodeModuleId { uuid, age }
+    }
+
+    /// Reads the CodeModuleId from an ELF, Mach-O or PE file.
+    ///
+    /// This is derived from the GNU build ID on Linux, the `LC_UUID` load
+    /// command on macOS and the CodeView record on Windows, and matches the
+    /// identifier of the same module in a minidump.
+    pub fn from_object_file(buffer: &[u8]) -> Result<CodeModuleId> {
+        object::module_id(buffer)
+    }
+
     /// Returns the UUID part of the code module's debug_identifier
     pub fn uuid(&self) -> Uuid {
         self.uuid
@@ -82,9 +138,9 @@ impl fmt::Display for CodeModuleId {
     }
 }
 
-impl Into<String> for CodeModuleId {
-    fn into(self) -> String {
-        self.to_string()
+impl From<CodeModuleId> for String {
+    fn from(id: CodeModuleId) -> String {
+        id.to_string()
     }
 }
 
@@ -95,8 +151,19 @@ pub struct CodeModule(c_void);
 
 impl CodeModule {
     /// Returns the unique identifier of this `CodeModule`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the debug identifier is empty or malformed, which is valid
+    /// in minidumps. Use `try_id` to handle such modules.
     pub fn id(&self) -> CodeModuleId {
-        CodeModuleId::parse(&self.debug_identifier()).unwrap()
+        self.try_id().expect("invalid debug identifier")
+    }
+
+    /// Returns the unique identifier of this `CodeModule`, or `None` if the
+    /// debug identifier is empty or cannot be parsed.
+    pub fn try_id(&self) -> Option<CodeModuleId> {
+        CodeModuleId::parse(&self.debug_identifier()).ok()
     }
 
     /// Returns the base address of this code module as it was loaded by the
@@ -156,25 +223,40 @@ impl CodeModule {
             utils::ptr_to_string(ptr)
         }
     }
+
+    /// Returns the key used to compare code modules. Modules without a valid
+    /// identifier are distinguished by their raw debug identifier, base
+    /// address and code file.
+    fn key(&self) -> (Option<CodeModuleId>, String, u64, String) {
+        match self.try_id() {
+            Some(id) => (Some(id), String::new(), 0, String::new()),
+            None => (
+                None,
+                self.debug_identifier(),
+                self.base_address(),
+                self.code_file(),
+            ),
+        }
+    }
 }
 
 impl Eq for CodeModule {}
 
 impl PartialEq for CodeModule {
     fn eq(&self, other: &Self) -> bool {
-        self.id() == other.id()
+        self.key() == other.key()
     }
 }
 
 impl Hash for CodeModule {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.id().hash(state)
+        self.key().hash(state)
     }
 }
 
 impl Ord for CodeModule {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.id().cmp(&other.id())
+        self.key().cmp(&other.key())
     }
 }
 
@@ -187,7 +269,7 @@ impl PartialOrd for CodeModule {
 impl fmt::Debug for CodeModule {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("CodeModule")
-            .field("id", &self.id())
+            .field("id", &self.try_id())
             .field("base_address", &self.base_address())
             .field("size", &self.size())
             .field("code_file", &self.code_file())
@@ -219,9 +301,17 @@ fn test_to_string() {
     assert_eq!(id.to_string(), "DFB8E43AF2423D73A453AEB6A777EF75A");
 }
 
+#[test]
+fn test_parse_long_age() {
+    let id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF751A").unwrap();
+    assert_eq!(id.age(), 26);
+    assert_eq!(id.to_string(), "DFB8E43AF2423D73A453AEB6A777EF751A");
+}
+
 #[test]
 fn test_parse_error() {
     assert!(CodeModuleId::parse("DFB8E43AF2423D73A").is_err());
+    assert!(CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF7\u{e9}0").is_err());
 }
 
 /// Indicates how well the instruction pointer derived during
@@ -230,7 +320,7 @@ fn test_parse_error() {
 ///
 /// In rough order of "trust metric".
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum FrameTrust {
     /// Unknown trust.
     None,
@@ -254,13 +344,51 @@ pub enum FrameTrust {
     Context,
 }
 
+/// The CPU family of a `StackFrame`.
+///
+/// Stack frames carry the register context of the CPU the minidump was written
+/// on. The family determines how return addresses are adjusted to point into
+/// the call instruction.
+#[repr(C)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum CpuFamily {
+    /// The CPU family could not be determined.
+    Unknown,
+
+    /// 32-bit x86.
+    X86,
+
+    /// 64-bit x86.
+    AMD64,
+
+    /// 32-bit ARM.
+    ARM,
+
+    /// 64-bit ARM.
+    ARM64,
+
+    /// 32-bit PowerPC.
+    PPC,
+
+    /// 64-bit PowerPC.
+    PPC64,
+
+    /// 32-bit SPARC.
+    SPARC,
+
+    /// 32-bit MIPS.
+    MIPS,
+}
+
 /// Contains information from the memorydump, especially the frame's instruction
 /// pointer. Also references an optional `CodeModule` that contains the
 /// instruction of this stack frame.
 ///
 /// Use a `Resolver` to fill a stack frame with source code information. The
 /// resolver needs symbols for this frame's `CodeModule` in order to provide
-/// debug information.
+/// debug information. Alternatively, supply complete symbols in the
+/// `FrameInfoMap` when processing the minidump to obtain source code
+/// information during stackwalking.
 #[repr(C)]
 pub struct StackFrame(c_void);
 
@@ -284,14 +412,48 @@ impl StackFrame {
     ///
     /// On some architectures, the return address as saved on the stack or in
     /// a register is fine for looking up the point of the call. On others, it
-    /// requires adjustment. ReturnAddress returns the address as saved by the
-    /// machine.
+    /// requires adjustment. `return_address` returns the address as saved by
+    /// the machine.
     ///
     /// Use `trust` to obtain how trustworthy this instruction is.
     pub fn instruction(&self) -> u64 {
         unsafe { stack_frame_instruction(self) }
     }
 
+    /// Returns the actual return address, as saved on the stack or in a
+    /// register.
+    ///
+    /// For the innermost frame, this is the program counter and equal to
+    /// `instruction`. For all other frames, this is the address at which
+    /// control will resume once the callee returns.
+    pub fn return_address(&self) -> u64 {
+        unsafe { stack_frame_return_address(self) }
+    }
+
+    /// Returns the number of bytes that Breakpad's stackwalker subtracted from
+    /// the `return_address` of this frame to obtain its `instruction`.
+    ///
+    /// This is zero for the innermost frame, and for all frames on
+    /// architectures where the return address is used for lookups as is.
+    pub fn instruction_adjustment(&self) -> u64 {
+        self.return_address().saturating_sub(self.instruction())
+    }
+
+    /// Returns the absolute address that should be used to look up symbols
+    /// and source locations for this frame.
+    ///
+    /// This is the `return_address` minus the `instruction_adjustment`, and
+    /// thus the same address that Breakpad's stackwalker looks up. Use this
+    /// to symbolicate frames outside of Breakpad.
+    pub fn lookup_address(&self) -> u64 {
+        self.return_address() - self.instruction_adjustment()
+    }
+
+    /// Returns the CPU family of this frame's register context.
+    pub fn cpu_family(&self) -> CpuFamily {
+        unsafe { stack_frame_cpu_family(self) }
+    }
+
     /// Returns the `CodeModule` that contains this frame's instruction.
     pub fn module(&self) -> Option<&CodeModule> {
         unsafe { stack_frame_module(self).as_ref() }
@@ -301,6 +463,48 @@ impl StackFrame {
     pub fn trust(&self) -> FrameTrust {
         unsafe { stack_frame_trust(self) }
     }
+
+    /// Returns the function name that contains the instruction. Can be empty
+    /// if no symbols with FUNC or PUBLIC records were supplied for this
+    /// frame's module during stackwalking, or before running the `Resolver`.
+    pub fn function_name(&self) -> Cow<'_, str> {
+        unsafe {
+            let ptr = stack_frame_function_name(self);
+            CStr::from_ptr(ptr).to_string_lossy()
+        }
+    }
+
+    /// Returns the source code file name in which the instruction was
+    /// declared. Can be empty if no symbols with line records were supplied
+    /// for this frame's module during stackwalking, or before running the
+    /// `Resolver`.
+    pub fn source_file_name(&self) -> Cow<'_, str> {
+        unsafe {
+            let ptr = stack_frame_source_file_name(self);
+            CStr::from_ptr(ptr).to_string_lossy()
+        }
+    }
+
+    /// Returns the source code line at which the instruction was declared.
+    /// Can be empty if no symbols with line records were supplied for this
+    /// frame's module during stackwalking, or before running the `Resolver`.
+    pub fn source_line(&self) -> c_int {
+        unsafe { stack_frame_source_line(self) }
+    }
+
+    /// Returns the absolute start address of the function that contains the
+    /// instruction. Zero if no symbols with FUNC or PUBLIC records were
+    /// supplied for this frame's module during stackwalking.
+    pub fn function_base(&self) -> u64 {
+        unsafe { stack_frame_function_base(self) }
+    }
+
+    /// Returns the absolute start address of the source line record that
+    /// contains the instruction. Zero if no symbols with line records were
+    /// supplied for this frame's module during stackwalking.
+    pub fn source_line_base(&self) -> u64 {
+        unsafe { stack_frame_source_line_base(self) }
+    }
 }
 
 impl fmt::Debug for StackFrame {
@@ -343,6 +547,184 @@ impl fmt::Debug for CallStack {
     }
 }
 
+/// Type of a STACK WIN record, corresponding to the frame data types in the
+/// Windows Debug Interface Access SDK.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum StackWinType {
+    /// Frame pointer omission data (`FPO_DATA`).
+    Fpo,
+
+    /// Kernel trap frame (`KTRAP_FRAME`).
+    Trap,
+
+    /// Kernel task state segment (`KTSS`).
+    Tss,
+
+    /// Standard EBP stackframe.
+    Standard,
+
+    /// Frame data with a program string (`FRAME_DATA`).
+    FrameData,
+}
+
+impl StackWinType {
+    /// Converts the numeric type used in STACK WIN records.
+    pub fn from_raw(raw: u32) -> Option<StackWinType> {
+        match raw {
+            0 => Some(StackWinType::Fpo),
+            1 => Some(StackWinType::Trap),
+            2 => Some(StackWinType::Tss),
+            3 => Some(StackWinType::Standard),
+            4 => Some(StackWinType::FrameData),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric type used in STACK WIN records.
+    pub fn to_raw(&self) -> u32 {
+        match *self {
+            StackWinType::Fpo => 0,
+            StackWinType::Trap => 1,
+            StackWinType::Tss => 2,
+            StackWinType::Standard => 3,
+            StackWinType::FrameData => 4,
+        }
+    }
+}
+
+/// Reason for the stackwalker to stop walking a thread.
+#[repr(C)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum WalkTermination {
+    /// The thread had no CPU context, so not even the first frame was found.
+    NoContext,
+
+    /// The stack reached the maximum number of frames.
+    MaxFrames,
+
+    /// None of the unwinding methods yielded a valid caller of the last frame.
+    /// See the last `FrameTrace` for the unwind information that was found.
+    ///
+    /// The stackwalker does not report why it stopped, so this is inferred
+    /// whenever a walk ends without reaching the maximum number of frames.
+    /// This includes callers that were rejected, for instance because their
+    /// stack pointer did not increase.
+    NoCaller,
+}
+
+/// Method used by the stackwalker to find the caller of a frame.
+#[repr(C)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum UnwindMethod {
+    /// No caller was found.
+    None,
+
+    /// A STACK WIN record (x86 only).
+    StackWin,
+
+    /// A STACK CFI record.
+    Cfi,
+
+    /// The frame pointer.
+    FramePointer,
+
+    /// Scanning the stack, starting at the CFA of a STACK CFI record.
+    CfiScan,
+
+    /// Scanning the stack for a plausible return address.
+    Scan,
+
+    /// An external stack walker.
+    Prewalked,
+}
+
+/// Decisions of the stackwalker at a single frame of a `CallStack`.
+///
+/// To find the caller of a frame, the stackwalker looks up unwind information
+/// at the frame's instruction. Depending on the CPU, it tries STACK WIN
+/// records, STACK CFI records, the frame pointer and finally stack scanning.
+/// The decisions are recorded while walking, in the order of the frames.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct FrameTrace {
+    /// How the stackwalker found this frame.
+    pub trust: FrameTrust,
+
+    /// How the stackwalker found the caller of this frame. `None` for the
+    /// last frame of the stack.
+    pub unwind_method: Option<UnwindMethod>,
+
+    /// Whether the stackwalker searched STACK CFI records at this frame.
+    pub cfi_searched: bool,
+
+    /// The STACK CFI rules found at this frame, which were used to recover the
+    /// caller's registers. The rules are serialized like in a STACK CFI record.
+    pub cfi_rules: Option<String>,
+
+    /// Whether the stackwalker searched STACK WIN records at this frame.
+    pub windows_searched: bool,
+
+    /// The type of the STACK WIN record found at this frame.
+    pub windows_type: Option<StackWinType>,
+
+    /// The program string of the STACK WIN record found at this frame.
+    pub windows_program_string: Option<String>,
+}
+
+/// Trace of all stackwalker decisions in a single `CallStack`.
+///
+/// Traces are only recorded if enabled with `Processor::set_stackwalk_trace`.
+/// Use `ProcessState::stackwalk_trace` to retrieve the trace of a thread.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StackwalkTrace {
+    /// Decisions at each frame, in the same order as `CallStack::frames`.
+    pub frames: Vec<FrameTrace>,
+
+    /// The reason why the stackwalker stopped after the last frame.
+    pub termination: WalkTermination,
+}
+
+/// Internal type used to transfer stackwalk traces over FFI
+#[repr(C)]
+struct FrameTraceEntry {
+    trust: FrameTrust,
+    unwind_method: UnwindMethod,
+    cfi_searched: bool,
+    cfi_rules: *const c_char,
+    windows_searched: bool,
+    windows_type: c_int,
+    windows_program_string: *const c_char,
+}
+
+/// Converts a weak pointer to an optional owned string
+fn optional_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+    }
+}
+
+impl<'a> From<&'a FrameTraceEntry> for FrameTrace {
+    fn from(entry: &'a FrameTraceEntry) -> FrameTrace {
+        FrameTrace {
+            trust: entry.trust,
+            unwind_method: match entry.unwind_method {
+                UnwindMethod::None => None,
+                method => Some(method),
+            },
+            cfi_searched: entry.cfi_searched,
+            cfi_rules: optional_string(entry.cfi_rules),
+            windows_searched: entry.windows_searched,
+            windows_type: if entry.windows_type < 0 {
+                None
+            } else {
+                StackWinType::from_raw(entry.windows_type as u32)
+            },
+            windows_program_string: optional_string(entry.windows_program_string),
+        }
+    }
+}
+
 /// Result of processing a Minidump or Microdump file.
 /// Usually included in `ProcessError` when the file cannot be processed.
 #[repr(C)]
@@ -400,6 +782,51 @@ struct SymbolEntry {
     symbol_data: *const u8,
 }
 
+/// Breakpad symbols prepared for a transfer over FFI
+///
+/// Keeps a reference to all debug identifier strings to extend their lifetime
+/// for as long as the entries are in use.
+struct SymbolEntries {
+    _identifiers: Vec<CString>,
+    entries: Vec<SymbolEntry>,
+}
+
+impl SymbolEntries {
+    fn new(frame_infos: Option<&FrameInfoMap>) -> SymbolEntries {
+        let identifiers: Vec<_> = frame_infos.map_or(Vec::new(), |s| {
+            s.keys()
+                .map(|k| CString::new(k.to_string()).unwrap_or_default())
+                .collect()
+        });
+
+        let entries = frame_infos.map_or(Vec::new(), |s| {
+            s.values()
+                .zip(identifiers.iter())
+                .map(|(v, id)| {
+                    SymbolEntry {
+                        debug_identifier: id.as_ptr(),
+                        symbol_size: v.len(),
+                        symbol_data: v.as_ptr(),
+                    }
+                })
+                .collect()
+        });
+
+        SymbolEntries {
+            _identifiers: identifiers,
+            entries,
+        }
+    }
+
+    fn as_ptr(&self) -> *const SymbolEntry {
+        self.entries.as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
 type IProcessState = c_void;
 
 /// Snapshot of the state of a processes during its crash. The object can be
@@ -417,6 +844,13 @@ pub struct ProcessState {
 /// This information is required by the stackwalker in case framepointers are
 /// missing in the raw stacktraces. Frame information is given as plain ASCII
 /// text as specified in the Breakpad symbol file specification.
+///
+/// Instead of STACK records only, the map may also contain complete symbol
+/// files. The stackwalker then uses FUNC and PUBLIC records to improve its
+/// results (for instance, parameter sizes for STACK WIN records on x86) and
+/// fills `StackFrame::function_name`, `source_file_name` and `source_line`
+/// for every frame. Use `SymbolicatedProcess::from_minidump_buffer` to walk
+/// and symbolicate a minidump with the same symbols in a single call.
 pub type FrameInfoMap<'a> = BTreeMap<CodeModuleId, &'a [u8]>;
 
 impl ProcessState {
@@ -440,38 +874,23 @@ impl ProcessState {
     /// process. The parameter `frame_infos` expects a map of Breakpad symbols
     /// containing STACK CFI and STACK WIN records to allow stackwalking with
     /// omitted frame pointers.
+    ///
+    /// Compressed minidumps are decompressed automatically if the respective
+    /// cargo feature is enabled, see `decompress`.
     pub fn from_minidump_buffer(
         buffer: &[u8],
         frame_infos: Option<&FrameInfoMap>,
     ) -> Result<ProcessState> {
-        let cfi_count = frame_infos.map_or(0, |s| s.len());
+        let buffer = utils::decompress(buffer)?;
+        let symbols = SymbolEntries::new(frame_infos);
         let mut result: ProcessResult = ProcessResult::Ok;
 
-        // Keep a reference to all CStrings to extend their lifetime
-        let cfi_vec: Vec<_> = frame_infos.map_or(Vec::new(), |s| {
-            s.iter()
-                .map(|(k, v)| (CString::new(k.to_string()), v.len(), v.as_ptr()))
-                .collect()
-        });
-
-        // Keep a reference to all symbol entries to extend their lifetime
-        let cfi_entries: Vec<_> = cfi_vec
-            .iter()
-            .map(|&(ref id, size, data)| {
-                SymbolEntry {
-                    debug_identifier: id.as_ref().map(|i| i.as_ptr()).unwrap_or(ptr::null()),
-                    symbol_size: size,
-                    symbol_data: data,
-                }
-            })
-            .collect();
-
         let internal = unsafe {
             process_minidump(
                 buffer.as_ptr() as *const c_char,
                 buffer.len(),
-                cfi_entries.as_ptr(),
-                cfi_count,
+                symbols.as_ptr(),
+                symbols.len(),
                 &mut result,
             )
         };
@@ -483,6 +902,71 @@ impl ProcessState {
         }
     }
 
+    /// Walks the stack of the thread at the given index again
+    ///
+    /// This allows to supply additional STACK CFI and STACK WIN records after
+    /// the minidump has been processed, without processing the entire dump
+    /// again. The minidump's memory and all stack frame information supplied
+    /// previously are retained. If `frame_infos` contains information for a
+    /// module that has been supplied before, it replaces the previous one. For
+    /// states created by a `Processor`, this also replaces the information in
+    /// the processor's cache.
+    ///
+    /// The index corresponds to the position of the thread in `threads`.
+    pub fn rewalk_thread(&mut self, index: usize, frame_infos: &FrameInfoMap) -> Result<()> {
+        self.rewalk_threads(&[index], frame_infos)
+    }
+
+    /// Walks the stacks of all threads again that reference one of the modules
+    /// in `frame_infos`
+    ///
+    /// Threads without frames in any of the supplied modules are not walked
+    /// again, since the additional information cannot change their stacks.
+    /// Modules without a valid identifier are skipped.
+    /// Returns the indexes of all threads that have been walked again. See
+    /// `rewalk_thread` for more information.
+    pub fn rewalk(&mut self, frame_infos: &FrameInfoMap) -> Result<Vec<usize>> {
+        let indexes: Vec<_> = self.threads()
+            .iter()
+            .enumerate()
+            .filter(|&(_, stack)| {
+                stack
+                    .frames()
+                    .iter()
+                    .filter_map(|frame| frame.module())
+                    .filter_map(CodeModule::try_id)
+                    .any(|id| frame_infos.contains_key(&id))
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        self.rewalk_threads(&indexes, frame_infos)?;
+        Ok(indexes)
+    }
+
+    fn rewalk_threads(&mut self, indexes: &[usize], frame_infos: &FrameInfoMap) -> Result<()> {
+        if indexes.is_empty() {
+            return Ok(());
+        }
+
+        let symbols = SymbolEntries::new(Some(frame_infos));
+        let success = unsafe {
+            process_state_rewalk(
+                self.internal,
+                indexes.as_ptr(),
+                indexes.len(),
+                symbols.as_ptr(),
+                symbols.len(),
+            )
+        };
+
+        if success {
+            Ok(())
+        } else {
+            Err(ProcessError(ProcessResult::ErrorGettingThread).into())
+        }
+    }
+
     /// Returns a list of `CallStack`s in the minidump.
     pub fn threads(&self) -> &[&CallStack] {
         unsafe {
@@ -493,6 +977,31 @@ impl ProcessState {
         }
     }
 
+    /// Returns the stackwalk trace of the thread at the given index
+    ///
+    /// The trace records how the stackwalker found every frame, which unwind
+    /// information it used and why it stopped. The index corresponds to the
+    /// position of the thread in `threads`. Returns `None` if the index is out
+    /// of bounds, or if the thread was not walked by a `Processor` with
+    /// tracing enabled.
+    pub fn stackwalk_trace(&self, thread_index: usize) -> Option<StackwalkTrace> {
+        let mut size = 0;
+        let mut termination = WalkTermination::NoContext;
+        let data = unsafe {
+            process_state_trace(self.internal, thread_index, &mut size, &mut termination)
+        };
+
+        if data.is_null() {
+            return None;
+        }
+
+        let entries = unsafe { slice::from_raw_parts(data, size) };
+        Some(StackwalkTrace {
+            frames: entries.iter().map(FrameTrace::from).collect(),
+            termination,
+        })
+    }
+
     /// Returns a list of all `CodeModule`s referenced in one of the `CallStack`s.
     pub fn referenced_modules(&self) -> HashSet<&CodeModule> {
         self.threads()
@@ -516,3 +1025,117 @@ impl fmt::Debug for ProcessState {
             .finish()
     }
 }
+
+type IProcessor = c_void;
+
+/// Minidump processor that retains stack frame information across minidumps
+///
+/// `ProcessState::from_minidump_buffer` parses all Breakpad symbols in the
+/// `FrameInfoMap` for every minidump. When processing many minidumps of the
+/// same application, the `Processor` only parses symbols for a `CodeModule`
+/// the first time it is encountered and reuses them for all subsequent
+/// minidumps. Symbols are identified by `CodeModuleId`, so different builds of
+/// a module never share symbols.
+///
+/// The cache grows with every new module. Use `evict` or `clear` to release
+/// symbols that are no longer needed.
+pub struct Processor {
+    internal: *mut IProcessor,
+}
+
+impl Processor {
+    /// Creates a new `Processor` with an empty cache
+    pub fn new() -> Processor {
+        let internal = unsafe { processor_new() };
+        Processor { internal }
+    }
+
+    /// Reads a minidump from the filesystem into memory and processes it
+    ///
+    /// See `process_minidump_buffer` for more information.
+    pub fn process_minidump_file<P: AsRef<Path>>(
+        &mut self,
+        file_path: P,
+        frame_infos: Option<&FrameInfoMap>,
+    ) -> Result<ProcessState> {
+        let buffer = utils::read_buffer(file_path)?;
+        self.process_minidump_buffer(buffer.as_slice(), frame_infos)
+    }
+
+    /// Processes a minidump supplied via raw binary data
+    ///
+    /// Returns a `ProcessState` that contains information about the crashed
+    /// process. The parameter `frame_infos` expects a map of Breakpad symbols
+    /// containing STACK CFI and STACK WIN records. Symbols are only loaded for
+    /// modules that are not in the cache, yet. Once loaded, they will be used
+    /// for all subsequent minidumps, even if they are omitted from
+    /// `frame_infos`.
+    pub fn process_minidump_buffer(
+        &mut self,
+        buffer: &[u8],
+        frame_infos: Option<&FrameInfoMap>,
+    ) -> Result<ProcessState> {
+        let buffer = utils::decompress(buffer)?;
+        let symbols = SymbolEntries::new(frame_infos);
+        let mut result: ProcessResult = ProcessResult::Ok;
+
+        let internal = unsafe {
+            processor_process(
+                self.internal,
+                buffer.as_ptr() as *const c_char,
+                buffer.len(),
+                symbols.as_ptr(),
+                symbols.len(),
+                &mut result,
+            )
+        };
+
+        if result == ProcessResult::Ok && !internal.is_null() {
+            Ok(ProcessState { internal })
+        } else {
+            Err(ProcessError(result).into())
+        }
+    }
+
+    /// Removes stack frame information of the given module from the cache
+    ///
+    /// Returns whether information had been loaded for this module. Existing
+    /// `ProcessState`s are not affected.
+    pub fn evict(&mut self, id: &CodeModuleId) -> bool {
+        let id = CString::new(id.to_string()).unwrap_or_default();
+        unsafe { processor_evict(self.internal, id.as_ptr()) }
+    }
+
+    /// Removes stack frame information of all modules from the cache
+    pub fn clear(&mut self) {
+        unsafe { processor_clear(self.internal) };
+    }
+
+    /// Enables or disables stackwalk traces for subsequently processed
+    /// minidumps
+    ///
+    /// Traces record the unwind information used for every frame and the
+    /// reason why stackwalking stopped, which helps to debug bad stacks. They
+    /// are disabled by default. See `ProcessState::stackwalk_trace`.
+    pub fn set_stackwalk_trace(&mut self, enabled: bool) {
+        unsafe { processor_set_trace(self.internal, enabled) };
+    }
+}
+
+impl Default for Processor {
+    fn default() -> Processor {
+        Processor::new()
+    }
+}
+
+impl Drop for Processor {
+    fn drop(&mut self) {
+        unsafe { processor_delete(self.internal) };
+    }
+}
+
+impl fmt::Debug for Processor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Processor").finish()
+    }
+}
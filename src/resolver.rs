@@ -1,71 +1,222 @@
 use std::fmt;
 use std::borrow::Cow;
-use std::ffi::CStr;
+use std::collections::BTreeMap;
 use std::ops::Deref;
-use std::os::raw::{c_char, c_int, c_void};
+use std::os::raw::c_int;
 use std::path::Path;
 
+use demangle::{demangle, DemangleOptions};
 use errors::Result;
 use errors::ErrorKind::ResolverError;
-use processor::StackFrame;
+use processor::{CodeModule, CodeModuleId, ProcessState, StackFrame};
+use symbol_file::ParseDiagnostic;
+use symbol_index::{IndexLayout, SymbolIndex};
+use symbols::{Files, Functions, ModuleInfo, PublicSymbols, SourceLocation, SymbolLookup, Symbols};
 use utils;
 
+pub use symbols::SymbolSource;
+
 extern "C" {
-    fn stack_frame_function_name(frame: *const StackFrame) -> *const c_char;
-    fn stack_frame_source_file_name(frame: *const StackFrame) -> *const c_char;
-    fn stack_frame_source_line(frame: *const StackFrame) -> c_int;
+    fn stack_frame_clone(frame: *const StackFrame) -> *mut StackFrame;
     fn stack_frame_delete(frame: *mut StackFrame);
+}
+
+/// A function that was inlined into the function of a `ResolvedStackFrame`
+/// or `SymbolInfo`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineFrame {
+    /// Name of the inlined function.
+    pub function_name: String,
+    /// Source code file in which the address was declared, if available.
+    pub source_file_name: Option<String>,
+    /// Source code line at which the address was declared, if available.
+    pub source_line: Option<u32>,
+}
+
+impl<'a> From<&'a SourceLocation<'a>> for InlineFrame {
+    fn from(location: &'a SourceLocation<'a>) -> InlineFrame {
+        InlineFrame {
+            function_name: location.function_name.to_string(),
+            source_file_name: location.file.map(str::to_string),
+            source_line: location.line,
+        }
+    }
+}
 
-    fn resolver_new(buffer: *const c_char, buffer_size: usize) -> *mut IResolver;
-    fn resolver_delete(resolver: *mut IResolver);
-    fn resolver_is_corrupt(resolver: *const IResolver) -> bool;
-    fn resolver_resolve_frame(
-        resolver: *const IResolver,
-        frame: *const StackFrame,
-    ) -> *mut StackFrame;
+/// Symbol information for an address within a code module, as returned by
+/// `Resolver::lookup`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolInfo {
+    /// Name of the function that contains the address.
+    pub function_name: String,
+    /// The kind of record the function name originates from.
+    pub symbol_source: SymbolSource,
+    /// Start address of the function relative to the code module.
+    pub function_address: u64,
+    /// Source code file in which the address was declared, if line records
+    /// are available for the function. If the address was inlined, this is
+    /// the file of the outermost call site.
+    pub source_file_name: Option<String>,
+    /// Source code line at which the address was declared, if line records
+    /// are available for the function. If the address was inlined, this is
+    /// the line of the outermost call site.
+    pub source_line: Option<u32>,
+    /// Functions inlined at the address, innermost first.
+    pub inlined_frames: Vec<InlineFrame>,
 }
 
 /// A resolved version of `StackFrame`. Contains source code locations and code
 /// offsets, if the resolver was able to locate symbols for this frame.
-/// Otherwise, the additional attributes are empty.
+/// Otherwise, the additional attributes are the ones of the original frame.
 ///
-/// `ResolvedStackFrame` implements `Deref` for `StackFrame`, so that it can be used
-/// interchangibly. See `StackFrame` for additional accessors.
+/// `ResolvedStackFrame` implements `Deref` for `StackFrame`, so that it can be
+/// used interchangibly. It shadows `function_name`, `source_file_name` and
+/// `source_line` with the resolved values. See `StackFrame` for all other
+/// accessors.
+///
+/// If the instruction was inlined into the frame's function, the resolved
+/// source location is the call site in the outermost function. The inlined
+/// functions are available via `inlined_frames`.
 pub struct ResolvedStackFrame {
     internal: *mut StackFrame,
+    symbol_source: SymbolSource,
+    record_address: Option<u64>,
+    function_name: String,
+    function_base: u64,
+    function_size: u64,
+    parameter_size: u64,
+    source_file_name: String,
+    source_line: c_int,
+    source_line_base: u64,
+    inlined_frames: Vec<InlineFrame>,
 }
 
 impl ResolvedStackFrame {
-    /// Creates a `ResolvedStackFrame` instance from a raw stack frame pointer.
-    /// The pointer is assumed to be owned, and the underlying memory will be
-    /// freed when this struct is dropped.
-    pub(crate) fn from_ptr(internal: *mut StackFrame) -> ResolvedStackFrame {
-        ResolvedStackFrame { internal }
+    /// Creates an unresolved copy of the given frame. All additional
+    /// attributes are taken from the input frame.
+    pub(crate) fn unresolved(frame: &StackFrame) -> ResolvedStackFrame {
+        ResolvedStackFrame {
+            internal: unsafe { stack_frame_clone(frame) },
+            symbol_source: SymbolSource::None,
+            record_address: None,
+            function_name: frame.function_name().into_owned(),
+            function_base: frame.function_base(),
+            function_size: 0,
+            parameter_size: 0,
+            source_file_name: frame.source_file_name().into_owned(),
+            source_line: frame.source_line(),
+            source_line_base: frame.source_line_base(),
+            inlined_frames: Vec::new(),
+        }
     }
 
-    /// Returns the function name that contains the instruction. Can be empty
-    /// before running the `Resolver` or if debug symbols are missing.
-    pub fn function_name(&self) -> Cow<str> {
-        unsafe {
-            let ptr = stack_frame_function_name(self.internal);
-            CStr::from_ptr(ptr).to_string_lossy()
+    /// Creates a copy of the given frame with the result of a symbol lookup.
+    /// Addresses in the lookup are relative to the given base address.
+    fn resolved(
+        frame: &StackFrame,
+        base_address: u64,
+        lookup: &SymbolLookup,
+    ) -> ResolvedStackFrame {
+        let location = &lookup.location;
+        ResolvedStackFrame {
+            internal: unsafe { stack_frame_clone(frame) },
+            symbol_source: lookup.source,
+            record_address: Some(lookup.function_address),
+            function_name: location.function_name.to_string(),
+            function_base: base_address + lookup.function_address,
+            function_size: lookup.function_size,
+            parameter_size: lookup.parameter_size,
+            source_file_name: location.file.unwrap_or("").to_string(),
+            source_line: location.line.unwrap_or(0) as c_int,
+            source_line_base: lookup
+                .line_address
+                .map_or(0, |address| base_address + address),
+            inlined_frames: lookup.inlined.iter().map(InlineFrame::from).collect(),
         }
     }
 
+    /// Returns the kind of symbol record that was used to resolve this frame.
+    /// Frames that were not resolved by the `Resolver` report
+    /// `SymbolSource::None`, even if they received a function name during
+    /// stackwalking.
+    pub fn symbol_source(&self) -> SymbolSource {
+        self.symbol_source
+    }
+
+    /// Returns the address of the FUNC or PUBLIC record that was used to
+    /// resolve this frame, relative to the start of the code module. Unlike
+    /// `function_base`, this address does not depend on where the module was
+    /// loaded.
+    pub fn record_address(&self) -> Option<u64> {
+        self.record_address
+    }
+
+    /// Returns the function name that contains the instruction. Can be empty
+    /// if no symbols were found for this frame.
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Returns the source code file name in which the instruction was
+    /// declared. Can be empty if no line records were found for this frame.
+    pub fn source_file_name(&self) -> &str {
+        &self.source_file_name
+    }
+
     /// Returns the source code line at which the instruction was declared.
-    /// Can be empty before running the `Resolver` or if debug symbols are
-    /// missing.
-    pub fn source_file_name(&self) -> Cow<str> {
-        unsafe {
-            let ptr = stack_frame_source_file_name(self.internal);
-            CStr::from_ptr(ptr).to_string_lossy()
+    /// Can be empty if no line records were found for this frame.
+    pub fn source_line(&self) -> c_int {
+        self.source_line
+    }
+
+    /// Returns the absolute start address of the function that contains the
+    /// instruction. Zero if no symbols were found for this frame.
+    pub fn function_base(&self) -> u64 {
+        self.function_base
+    }
+
+    /// Returns the size of the function that contains the instruction, as
+    /// declared by its FUNC record. Zero if no symbols were found for this
+    /// frame, or if it was resolved with a PUBLIC record, which does not
+    /// declare a size.
+    pub fn function_size(&self) -> u64 {
+        self.function_size
+    }
+
+    /// Returns the offset of the instruction from the start of its function,
+    /// suitable for rendering as `function+0x1c`. Zero if no symbols were
+    /// found for this frame.
+    ///
+    /// An offset larger than `function_size` indicates that the instruction
+    /// was attributed to a function that does not actually contain it.
+    pub fn function_offset(&self) -> u64 {
+        if self.function_base == 0 {
+            0
+        } else {
+            self.instruction().saturating_sub(self.function_base)
         }
     }
 
-    /// Returns the source code line at which the instruction was declared. Can
-    /// be empty before running the `Resolver` or if debug symbols are missing.
-    pub fn source_line(&self) -> c_int {
-        unsafe { stack_frame_source_line(self.internal) }
+    /// Returns the absolute start address of the source line record that
+    /// contains the instruction. Zero if no line records were found for this
+    /// frame.
+    pub fn source_line_base(&self) -> u64 {
+        self.source_line_base
+    }
+
+    /// Returns the size of the parameters passed to the function on the
+    /// stack, in bytes. Zero if no symbols were found for this frame, or if
+    /// this frame was not resolved by the `Resolver`.
+    pub fn parameter_size(&self) -> u64 {
+        self.parameter_size
+    }
+
+    /// Returns the functions inlined at this frame's instruction, innermost
+    /// first. Each inlined frame's source location points into the inlined
+    /// function, while its caller is the next entry in the list, or finally
+    /// this frame's function.
+    pub fn inlined_frames(&self) -> &[InlineFrame] {
+        &self.inlined_frames
     }
 }
 
@@ -96,11 +247,12 @@ impl fmt::Debug for ResolvedStackFrame {
     }
 }
 
-type IResolver = c_void;
-
 /// Source line resolver for stack frames. Handles Breakpad symbol files and
 /// searches them for instructions.
 ///
+/// The resolver supports FUNC, PUBLIC and line records, as well as INLINE and
+/// INLINE_ORIGIN records written by recent versions of `dump_syms`.
+///
 /// To use this resolver, obtain a list of referenced modules from a
 /// ProcessState and load all of them into the resolver. Once symbols have
 /// been loaded for a `CodeModule`, the resolver can fill frames with source
@@ -108,7 +260,32 @@ type IResolver = c_void;
 ///
 /// See `ResolvedStackFrame` for all available information.
 pub struct Resolver {
-    internal: *mut IResolver,
+    backend: Backend,
+    demangle_options: Option<DemangleOptions>,
+}
+
+/// Storage of the symbols of a `Resolver`.
+enum Backend {
+    /// Symbols parsed from a text symbol file.
+    Symbols(Symbols),
+    /// A `SymbolIndex` read directly from its buffer.
+    Index(IndexBackend),
+}
+
+type IndexData = dyn AsRef<[u8]> + Send + Sync;
+
+/// Buffer of a `SymbolIndex`, along with the layout and module that were read
+/// when validating it.
+struct IndexBackend {
+    data: Box<IndexData>,
+    layout: IndexLayout,
+    module: Option<ModuleInfo>,
+}
+
+impl IndexBackend {
+    fn open(&self) -> SymbolIndex<'_> {
+        SymbolIndex::with_layout((*self.data).as_ref(), self.layout)
+    }
 }
 
 impl Resolver {
@@ -120,33 +297,347 @@ impl Resolver {
     }
 
     /// Creates a new `Resolver` instance from a buffer containing Breakpad symbols
+    ///
+    /// Compressed symbols are decompressed automatically if the respective
+    /// cargo feature is enabled, see `decompress`.
     pub fn from_buffer(buffer: &[u8]) -> Result<Resolver> {
-        let internal = unsafe { resolver_new(buffer.as_ptr() as *const c_char, buffer.len()) };
+        let buffer = utils::decompress(buffer)?;
+        if buffer.is_empty() {
+            return Err(ResolverError("Could not load symbols".into()).into());
+        }
 
-        if internal.is_null() {
-            Err(ResolverError("Could not load symbols".into()).into())
-        } else {
-            Ok(Resolver { internal })
+        Ok(Resolver {
+            backend: Backend::Symbols(Symbols::parse(&buffer)),
+            demangle_options: None,
+        })
+    }
+
+    /// Creates a new `Resolver` instance from a `SymbolIndex` in the file
+    /// system.
+    ///
+    /// This reads the entire index into memory. Indexes are not compressed,
+    /// so to avoid the copy, memory-map the file and pass it to `from_index`
+    /// instead.
+    pub fn from_index_file<P: AsRef<Path>>(file_path: P) -> Result<Resolver> {
+        Self::from_index(utils::read_buffer(file_path)?)
+    }
+
+    /// Creates a new `Resolver` instance from a buffer containing a
+    /// `SymbolIndex`, such as a `Vec<u8>` or a memory-mapped file.
+    ///
+    /// Only the header of the index is validated. Lookups read directly from
+    /// the buffer, so creating the resolver does not depend on the size of
+    /// the symbols. A resolver created from an index never reports
+    /// diagnostics.
+    pub fn from_index<D>(data: D) -> Result<Resolver>
+    where
+        D: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        let (layout, module) = {
+            let index = SymbolIndex::parse(data.as_ref())?;
+            (index.layout(), index.module())
+        };
+
+        Ok(Resolver {
+            backend: Backend::Index(IndexBackend {
+                data: Box::new(data),
+                layout,
+                module,
+            }),
+            demangle_options: None,
+        })
+    }
+
+    /// Creates a new `Resolver` instance from a Breakpad symbol file in the
+    /// file system. Fails if any line in the file cannot be parsed.
+    pub fn from_file_strict<P: AsRef<Path>>(file_path: P) -> Result<Resolver> {
+        let buffer = utils::read_buffer(file_path)?;
+        Self::from_buffer_strict(buffer.as_slice())
+    }
+
+    /// Creates a new `Resolver` instance from a buffer containing Breakpad
+    /// symbols. Fails with a `ResolverError` describing the first line that
+    /// cannot be parsed.
+    pub fn from_buffer_strict(buffer: &[u8]) -> Result<Resolver> {
+        let resolver = Self::from_buffer(buffer)?;
+        if let Some(diagnostic) = resolver.diagnostics().first() {
+            return Err(ResolverError(diagnostic.to_string()).into());
         }
+
+        Ok(resolver)
     }
 
     /// Returns whether this `Resolver` is corrupt or it can be used to
-    /// resolve source line locations of `StackFrames`.
+    /// resolve source line locations of `StackFrames`. See `diagnostics` for
+    /// the lines that could not be parsed.
     pub fn corrupt(&self) -> bool {
-        unsafe { resolver_is_corrupt(self.internal) }
+        !self.diagnostics().is_empty()
+    }
+
+    /// Returns information about the code module declared in the MODULE
+    /// record of the symbol file. `None` if the file has no valid MODULE
+    /// record.
+    pub fn module(&self) -> Option<&ModuleInfo> {
+        match self.backend {
+            Backend::Symbols(ref symbols) => symbols.module.as_ref(),
+            Backend::Index(ref index) => index.module.as_ref(),
+        }
+    }
+
+    /// Returns all lines of the symbol file that were rejected by the parser,
+    /// ordered by line number. Rejected lines are skipped during symbol
+    /// resolution.
+    pub fn diagnostics(&self) -> &[ParseDiagnostic] {
+        match self.backend {
+            Backend::Symbols(ref symbols) => &symbols.diagnostics,
+            Backend::Index(..) => &[],
+        }
+    }
+
+    /// Looks up an address relative to the start of the code module.
+    fn lookup_symbol(&self, address: u64) -> Option<SymbolLookup<'_>> {
+        match self.backend {
+            Backend::Symbols(ref symbols) => symbols.lookup(address),
+            Backend::Index(ref index) => index.open().lookup(address),
+        }
     }
 
     /// Tries to locate the frame's instruction in the loaded code modules.
     /// Returns a resolved stack frame instance. If no  symbols can be found
     /// for the frame, a clone of the input is returned.
     pub fn resolve_frame(&self, frame: &StackFrame) -> ResolvedStackFrame {
-        let ptr = unsafe { resolver_resolve_frame(self.internal, frame) };
-        ResolvedStackFrame::from_ptr(ptr)
+        let base_address = match frame.module() {
+            Some(module) => module.base_address(),
+            None => return ResolvedStackFrame::unresolved(frame),
+        };
+
+        let lookup = frame
+            .instruction()
+            .checked_sub(base_address)
+            .and_then(|address| self.lookup_symbol(address));
+
+        let lookup = match lookup {
+            Some(lookup) => lookup,
+            None => return ResolvedStackFrame::unresolved(frame),
+        };
+
+        let mut resolved = ResolvedStackFrame::resolved(frame, base_address, &lookup);
+        self.demangle_name(&mut resolved.function_name);
+        for inlined in &mut resolved.inlined_frames {
+            self.demangle_name(&mut inlined.function_name);
+        }
+
+        resolved
+    }
+
+    /// Sets options to demangle function names of resolved frames and
+    /// lookups. By default, names are returned as written to the symbol file.
+    /// Pass `None` to disable demangling again.
+    ///
+    /// See `demangle` for the supported languages.
+    pub fn set_demangle_options(&mut self, options: Option<DemangleOptions>) {
+        self.demangle_options = options;
+    }
+
+    /// Demangles the name in place, if demangling is enabled.
+    fn demangle_name(&self, name: &mut String) {
+        if let Some(ref options) = self.demangle_options {
+            if let Cow::Owned(demangled) = demangle(name, options) {
+                *name = demangled;
+            }
+        }
+    }
+
+    /// Returns an iterator over all FILE records, yielding the identifier
+    /// and name of each source file.
+    pub fn files(&self) -> Files<'_> {
+        match self.backend {
+            Backend::Symbols(ref symbols) => symbols.files(),
+            Backend::Index(ref index) => Files::from_index(index.open()),
+        }
+    }
+
+    /// Returns the name of the source file with the given FILE identifier, as
+    /// referenced by `Line::file_id`.
+    pub fn file_name(&self, id: u32) -> Option<&str> {
+        match self.backend {
+            Backend::Symbols(ref symbols) => symbols.file_name(id),
+            Backend::Index(ref index) => index.open().file_name(id),
+        }
+    }
+
+    /// Returns an iterator over all functions declared by FUNC records,
+    /// ordered by address. Use `Function::lines` to access the line records
+    /// of each function.
+    ///
+    /// For resolvers created from a `SymbolIndex`, every function is read
+    /// from the index while iterating.
+    pub fn functions(&self) -> Functions<'_> {
+        match self.backend {
+            Backend::Symbols(ref symbols) => symbols.functions(),
+            Backend::Index(ref index) => Functions::from_index(index.open()),
+        }
+    }
+
+    /// Returns an iterator over all PUBLIC records, ordered by address.
+    pub fn public_symbols(&self) -> PublicSymbols<'_> {
+        match self.backend {
+            Backend::Symbols(ref symbols) => symbols.publics(),
+            Backend::Index(ref index) => PublicSymbols::from_index(index.open()),
+        }
+    }
+
+    /// Resolves the frame like `resolve_frame`, but first verifies that the
+    /// frame's code module matches the MODULE record of the symbol file.
+    ///
+    /// Fails with a `ResolverError` if the frame has no module or its module
+    /// has no valid identifier, the symbol file does not declare a module, or
+    /// the identifiers differ. This prevents resolving frames with symbols of
+    /// a different build.
+    pub fn resolve_frame_checked(&self, frame: &StackFrame) -> Result<ResolvedStackFrame> {
+        let module = match frame.module() {
+            Some(module) => module,
+            None => return Err(ResolverError("Frame has no code module".into()).into()),
+        };
+
+        let frame_id = match module.try_id() {
+            Some(id) => id,
+            None => {
+                let message = format!(
+                    "Code module has an invalid identifier \"{}\"",
+                    module.debug_identifier()
+                );
+                return Err(ResolverError(message).into());
+            }
+        };
+
+        let symbols_id = match self.module() {
+            Some(module) => module.id,
+            None => return Err(ResolverError("Symbols have no module record".into()).into()),
+        };
+
+        if frame_id != symbols_id {
+            let message = format!(
+                "Code module {} does not match symbols for {}",
+                frame_id, symbols_id
+            );
+            return Err(ResolverError(message).into());
+        }
+
+        Ok(self.resolve_frame(frame))
+    }
+
+    /// Looks up symbol information for an address relative to the start of
+    /// the code module. This can be used to symbolicate addresses that do not
+    /// originate from a `ProcessState`, such as addresses from logs or
+    /// profilers.
+    ///
+    /// Returns `None` if no FUNC or PUBLIC record covers the address.
+    pub fn lookup(&self, relative_address: u64) -> Option<SymbolInfo> {
+        let lookup = self.lookup_symbol(relative_address)?;
+        let location = &lookup.location;
+
+        let mut info = SymbolInfo {
+            function_name: location.function_name.to_string(),
+            symbol_source: lookup.source,
+            function_address: lookup.function_address,
+            source_file_name: location.file.map(str::to_string),
+            source_line: location.line,
+            inlined_frames: lookup.inlined.iter().map(InlineFrame::from).collect(),
+        };
+
+        self.demangle_name(&mut info.function_name);
+        for inlined in &mut info.inlined_frames {
+            self.demangle_name(&mut inlined.function_name);
+        }
+
+        Some(info)
+    }
+}
+
+/// A collection of `Resolver`s for multiple code modules.
+///
+/// Each `Resolver` only contains symbols for a single code module, and it
+/// cannot tell whether a `StackFrame` belongs to that module. The cache stores
+/// resolvers by `CodeModuleId` and dispatches every frame to the resolver of
+/// its module. Frames of modules without a resolver remain unresolved.
+#[derive(Default)]
+pub struct SymbolCache {
+    resolvers: BTreeMap<CodeModuleId, Resolver>,
+}
+
+impl SymbolCache {
+    /// Creates a new, empty `SymbolCache`
+    pub fn new() -> SymbolCache {
+        SymbolCache::default()
+    }
+
+    /// Adds the resolver for the code module with the given identifier.
+    /// Returns the resolver previously stored for this module, if any.
+    pub fn insert(&mut self, id: CodeModuleId, resolver: Resolver) -> Option<Resolver> {
+        self.resolvers.insert(id, resolver)
+    }
+
+    /// Removes and returns the resolver for the given code module
+    pub fn remove(&mut self, id: &CodeModuleId) -> Option<Resolver> {
+        self.resolvers.remove(id)
+    }
+
+    /// Returns the resolver for the given code module
+    pub fn get(&self, id: &CodeModuleId) -> Option<&Resolver> {
+        self.resolvers.get(id)
+    }
+
+    /// Returns whether the cache contains a resolver for the given code module
+    pub fn contains(&self, id: &CodeModuleId) -> bool {
+        self.resolvers.contains_key(id)
+    }
+
+    /// Returns the number of code modules in this cache
+    pub fn len(&self) -> usize {
+        self.resolvers.len()
+    }
+
+    /// Returns whether this cache contains no resolvers
+    pub fn is_empty(&self) -> bool {
+        self.resolvers.is_empty()
+    }
+
+    /// Resolves the frame with the resolver of the frame's code module.
+    /// If the frame has no module, its module has no valid identifier or
+    /// there is no resolver for its module, an unresolved clone of the input
+    /// is returned.
+    pub fn resolve_frame(&self, frame: &StackFrame) -> ResolvedStackFrame {
+        let id = frame.module().and_then(CodeModule::try_id);
+        match id.and_then(|id| self.get(&id)) {
+            Some(resolver) => resolver.resolve_frame(frame),
+            None => ResolvedStackFrame::unresolved(frame),
+        }
+    }
+
+    /// Resolves all frames of all threads in the given `ProcessState`
+    ///
+    /// Returns one list of resolved frames per thread, in the same order as
+    /// `ProcessState::threads`.
+    pub fn resolve_process_state(&self, state: &ProcessState) -> Vec<Vec<ResolvedStackFrame>> {
+        state
+            .threads()
+            .iter()
+            .map(|stack| {
+                stack
+                    .frames()
+                    .iter()
+                    .map(|frame| self.resolve_frame(frame))
+                    .collect()
+            })
+            .collect()
     }
 }
 
-impl Drop for Resolver {
-    fn drop(&mut self) {
-        unsafe { resolver_delete(self.internal) };
+impl fmt::Debug for SymbolCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SymbolCache")
+            .field("modules", &self.resolvers.keys().collect::<Vec<_>>())
+            .finish()
     }
 }
@@ -0,0 +1,1054 @@
+use std::io::Write;
+use std::path::Path;
+use std::{fmt, slice, str};
+
+use errors::ErrorKind::SymbolFileError;
+use elf;
+use errors::Result;
+use mach;
+use merge::{self, MergeOptions, MergedSymbolFile};
+use processor::{CodeModuleId, StackWinType};
+use utils;
+
+/// The type of a record in a Breakpad symbol file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum RecordType {
+    /// The MODULE header record.
+    Module,
+    /// An INFO record with additional module information.
+    Info,
+    /// A FILE record declaring a source file.
+    File,
+    /// A FUNC record declaring a function.
+    Func,
+    /// A line record following a FUNC record. Lines with an unknown record
+    /// type are treated as line records.
+    Line,
+    /// A PUBLIC record declaring an exported symbol.
+    Public,
+    /// An INLINE_ORIGIN record declaring the name of inlined functions.
+    InlineOrigin,
+    /// An INLINE record declaring inlined ranges of a function.
+    Inline,
+    /// A STACK CFI or STACK WIN record with unwind information.
+    Stack,
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            RecordType::Module => "MODULE",
+            RecordType::Info => "INFO",
+            RecordType::File => "FILE",
+            RecordType::Func => "FUNC",
+            RecordType::Line => "line",
+            RecordType::Public => "PUBLIC",
+            RecordType::InlineOrigin => "INLINE_ORIGIN",
+            RecordType::Inline => "INLINE",
+            RecordType::Stack => "STACK",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A line in a symbol file that was rejected by the parser.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseDiagnostic {
+    /// One-based line number in the symbol file.
+    pub line: usize,
+    /// The type of the rejected record.
+    pub record_type: RecordType,
+    /// Reason why the record was rejected.
+    pub reason: String,
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: invalid {} record: {}",
+            self.line, self.record_type, self.reason
+        )
+    }
+}
+
+/// The `MODULE <os> <arch> <id> <name>` record at the start of a symbol file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleRecord {
+    /// Operating system the module was built for, e.g. `mac` or `windows`.
+    pub os: String,
+    /// CPU architecture of the module, e.g. `x86_64`.
+    pub arch: String,
+    /// Identifier of the module, matching `CodeModule::id`.
+    pub id: CodeModuleId,
+    /// Name of the file containing debug information.
+    pub name: String,
+}
+
+/// An `INFO` record with additional information about the module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InfoRecord {
+    /// `INFO CODE_ID <code id> [<code file>]`, declaring the identifier and
+    /// name of the executable or library.
+    CodeId {
+        /// Identifier of the executable or library.
+        code_id: String,
+        /// Name of the executable or library, usually only on Windows.
+        code_file: Option<String>,
+    },
+    /// Any other INFO record, containing the text after `INFO `.
+    Other(String),
+}
+
+/// A `FILE <id> <name>` record declaring a source file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileRecord {
+    /// Identifier referenced by line and INLINE records.
+    pub id: u32,
+    /// Path of the source file.
+    pub name: String,
+}
+
+/// An `INLINE_ORIGIN <id> <name>` record declaring an inlined function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineOriginRecord {
+    /// Identifier referenced by INLINE records.
+    pub id: u32,
+    /// Name of the inlined function. In files with legacy INLINE records,
+    /// this is preceded by the identifier of the file declaring the function.
+    pub name: String,
+}
+
+/// A `FUNC [m] <address> <size> <parameter size> <name>` record.
+///
+/// The line and INLINE records following a FUNC record are only attached by
+/// `SymbolFile`. When streaming `Records`, they are returned separately and
+/// these lists are empty.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FuncRecord {
+    /// Whether multiple symbols were folded into this function.
+    pub multiple: bool,
+    /// Start address of the function, relative to the code module.
+    pub address: u64,
+    /// Number of bytes covered by the function.
+    pub size: u64,
+    /// Size of the parameters passed to the function on the stack.
+    pub parameter_size: u64,
+    /// Name of the function.
+    pub name: String,
+    /// INLINE records of this function.
+    pub inlines: Vec<InlineRecord>,
+    /// Line records of this function.
+    pub lines: Vec<LineRecord>,
+}
+
+/// An `INLINE <depth> <call line> <call file> <origin> [<address> <size>]+`
+/// record, declaring ranges of a function that were inlined from an origin.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineRecord {
+    /// Nesting depth of the inlined call, starting at zero.
+    pub depth: u32,
+    /// Line number of the call site.
+    pub call_line: u32,
+    /// File identifier of the call site. `None` in legacy INLINE records.
+    pub call_file: Option<u32>,
+    /// Identifier of the INLINE_ORIGIN record.
+    pub origin_id: u32,
+    /// Inlined address ranges as start address and size.
+    pub ranges: Vec<(u64, u64)>,
+}
+
+/// A `<address> <size> <line> <file>` record following a FUNC record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineRecord {
+    /// Start address of the line record, relative to the code module.
+    pub address: u64,
+    /// Number of bytes covered by this line record.
+    pub size: u64,
+    /// Line number in the source file.
+    pub line: u32,
+    /// Identifier of the FILE record declaring the source file.
+    pub file_id: u32,
+}
+
+/// A `PUBLIC [m] <address> <parameter size> <name>` record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublicRecord {
+    /// Whether multiple symbols share this address.
+    pub multiple: bool,
+    /// Address of the symbol, relative to the code module.
+    pub address: u64,
+    /// Size of the parameters passed to the function on the stack.
+    pub parameter_size: u64,
+    /// Name of the symbol.
+    pub name: String,
+}
+
+/// A `STACK CFI INIT <address> <size> <rules>` record, declaring the unwind
+/// rules at the start of an address range.
+///
+/// The `STACK CFI` records following it are only attached by `SymbolFile`.
+/// When streaming `Records`, they are returned separately and `deltas` is
+/// empty.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StackCfiInitRecord {
+    /// Start address of the range, relative to the code module.
+    pub address: u64,
+    /// Number of bytes covered by the rules.
+    pub size: u64,
+    /// Unwind rules at the start address, as `<register>: <expression>`
+    /// pairs. The parser ensures that the rules include `.cfa`.
+    pub rules: String,
+    /// Changes to the rules at subsequent addresses in the range.
+    pub deltas: Vec<StackCfiRecord>,
+}
+
+/// A `STACK CFI <address> <rules>` record, changing unwind rules within the
+/// range of the preceding STACK CFI INIT record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StackCfiRecord {
+    /// Address from which the rules apply, relative to the code module.
+    pub address: u64,
+    /// Changed unwind rules.
+    pub rules: String,
+}
+
+/// A `STACK WIN` record with Windows unwind information.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StackWinRecord {
+    /// Type of the frame data.
+    pub frame_type: StackWinType,
+    /// Start address of the range, relative to the code module.
+    pub address: u64,
+    /// Number of bytes covered by the record.
+    pub size: u64,
+    /// Size of the function prologue.
+    pub prologue_size: u32,
+    /// Size of the function epilogue.
+    pub epilogue_size: u32,
+    /// Size of the parameters passed to the function on the stack.
+    pub parameter_size: u32,
+    /// Size of the callee-saved registers.
+    pub saved_register_size: u32,
+    /// Size of the local variables.
+    pub local_size: u32,
+    /// Maximum number of bytes pushed on the stack.
+    pub max_stack_size: u32,
+    /// Postfix program to recover the caller's registers, if any.
+    pub program_string: Option<String>,
+    /// Whether the function uses EBP as a frame pointer. Only used if there
+    /// is no program string.
+    pub allocates_base_pointer: bool,
+}
+
+/// A single record of a Breakpad symbol file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Record {
+    /// A MODULE record.
+    Module(ModuleRecord),
+    /// An INFO record.
+    Info(InfoRecord),
+    /// A FILE record.
+    File(FileRecord),
+    /// An INLINE_ORIGIN record.
+    InlineOrigin(InlineOriginRecord),
+    /// A FUNC record.
+    Func(FuncRecord),
+    /// An INLINE record belonging to the preceding FUNC record.
+    Inline(InlineRecord),
+    /// A line record belonging to the preceding FUNC record.
+    Line(LineRecord),
+    /// A PUBLIC record.
+    Public(PublicRecord),
+    /// A STACK CFI INIT record.
+    StackCfiInit(StackCfiInitRecord),
+    /// A STACK CFI record belonging to the preceding STACK CFI INIT record.
+    StackCfi(StackCfiRecord),
+    /// A STACK WIN record.
+    StackWin(StackWinRecord),
+}
+
+impl Record {
+    /// Returns the type of this record.
+    pub fn record_type(&self) -> RecordType {
+        match *self {
+            Record::Module(_) => RecordType::Module,
+            Record::Info(_) => RecordType::Info,
+            Record::File(_) => RecordType::File,
+            Record::InlineOrigin(_) => RecordType::InlineOrigin,
+            Record::Func(_) => RecordType::Func,
+            Record::Inline(_) => RecordType::Inline,
+            Record::Line(_) => RecordType::Line,
+            Record::Public(_) => RecordType::Public,
+            Record::StackCfiInit(_) | Record::StackCfi(_) | Record::StackWin(_) => {
+                RecordType::Stack
+            }
+        }
+    }
+}
+
+impl fmt::Display for ModuleRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MODULE {} {} {} {}", self.os, self.arch, self.id, self.name)
+    }
+}
+
+impl fmt::Display for InfoRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InfoRecord::CodeId {
+                ref code_id,
+                code_file: Some(ref code_file),
+            } => write!(f, "INFO CODE_ID {} {}", code_id, code_file),
+            InfoRecord::CodeId { ref code_id, .. } => write!(f, "INFO CODE_ID {}", code_id),
+            InfoRecord::Other(ref text) => write!(f, "INFO {}", text),
+        }
+    }
+}
+
+impl fmt::Display for FileRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FILE {} {}", self.id, self.name)
+    }
+}
+
+impl fmt::Display for InlineOriginRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "INLINE_ORIGIN {} {}", self.id, self.name)
+    }
+}
+
+/// Formats the FUNC line only. Use `SymbolFile::write` to include the line
+/// and INLINE records.
+impl fmt::Display for FuncRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FUNC ")?;
+        if self.multiple {
+            write!(f, "m ")?;
+        }
+
+        write!(
+            f,
+            "{:x} {:x} {:x} {}",
+            self.address, self.size, self.parameter_size, self.name
+        )
+    }
+}
+
+impl fmt::Display for InlineRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "INLINE {} {}", self.depth, self.call_line)?;
+        if let Some(call_file) = self.call_file {
+            write!(f, " {}", call_file)?;
+        }
+
+        write!(f, " {}", self.origin_id)?;
+        for &(address, size) in &self.ranges {
+            write!(f, " {:x} {:x}", address, size)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LineRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:x} {:x} {} {}",
+            self.address, self.size, self.line, self.file_id
+        )
+    }
+}
+
+impl fmt::Display for PublicRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PUBLIC ")?;
+        if self.multiple {
+            write!(f, "m ")?;
+        }
+
+        write!(f, "{:x} {:x} {}", self.address, self.parameter_size, self.name)
+    }
+}
+
+/// Formats the STACK CFI INIT line only. Use `SymbolFile::write` to include
+/// the STACK CFI records.
+impl fmt::Display for StackCfiInitRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "STACK CFI INIT {:x} {:x} {}",
+            self.address, self.size, self.rules
+        )
+    }
+}
+
+impl fmt::Display for StackCfiRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "STACK CFI {:x} {}", self.address, self.rules)
+    }
+}
+
+impl fmt::Display for StackWinRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "STACK WIN {:x} {:x} {:x} {:x} {:x} {:x} {:x} {:x} {:x} ",
+            self.frame_type.to_raw(),
+            self.address,
+            self.size,
+            self.prologue_size,
+            self.epilogue_size,
+            self.parameter_size,
+            self.saved_register_size,
+            self.local_size,
+            self.max_stack_size
+        )?;
+
+        match self.program_string {
+            Some(ref program_string) => write!(f, "1 {}", program_string),
+            None => write!(f, "0 {}", self.allocates_base_pointer as u8),
+        }
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Record::Module(ref record) => record.fmt(f),
+            Record::Info(ref record) => record.fmt(f),
+            Record::File(ref record) => record.fmt(f),
+            Record::InlineOrigin(ref record) => record.fmt(f),
+            Record::Func(ref record) => record.fmt(f),
+            Record::Inline(ref record) => record.fmt(f),
+            Record::Line(ref record) => record.fmt(f),
+            Record::Public(ref record) => record.fmt(f),
+            Record::StackCfiInit(ref record) => record.fmt(f),
+            Record::StackCfi(ref record) => record.fmt(f),
+            Record::StackWin(ref record) => record.fmt(f),
+        }
+    }
+}
+
+/// Streaming parser over the records of a Breakpad symbol file.
+///
+/// Yields one record per non-empty line, or a `ParseDiagnostic` if the line
+/// cannot be parsed. Records are returned in the order of the file.
+#[derive(Clone)]
+pub struct Records<'a> {
+    lines: slice::Split<'a, u8, fn(&u8) -> bool>,
+    line_number: usize,
+}
+
+impl<'a> Records<'a> {
+    /// Creates a parser over a buffer containing a Breakpad symbol file.
+    pub fn new(buffer: &'a [u8]) -> Records<'a> {
+        fn is_newline(byte: &u8) -> bool {
+            *byte == b'\n'
+        }
+
+        Records {
+            lines: buffer.split(is_newline),
+            line_number: 0,
+        }
+    }
+
+    /// Returns the one-based line number of the last returned record.
+    pub(crate) fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = ::std::result::Result<Record, ParseDiagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in &mut self.lines {
+            self.line_number += 1;
+
+            let line = String::from_utf8_lossy(line);
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return Some(parse_record(line).map_err(|(record_type, reason)| {
+                ParseDiagnostic {
+                    line: self.line_number,
+                    record_type,
+                    reason: reason.to_string(),
+                }
+            }));
+        }
+
+        None
+    }
+}
+
+impl<'a> fmt::Debug for Records<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Records")
+            .field("line_number", &self.line_number)
+            .finish()
+    }
+}
+
+/// A parsed Breakpad symbol file.
+///
+/// Line and INLINE records are attached to their FUNC record, and STACK CFI
+/// records to their STACK CFI INIT record. All other records are kept in the
+/// order of the file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SymbolFile {
+    /// The MODULE record, if present.
+    pub module: Option<ModuleRecord>,
+    /// All INFO records.
+    pub info: Vec<InfoRecord>,
+    /// All FILE records.
+    pub files: Vec<FileRecord>,
+    /// All INLINE_ORIGIN records.
+    pub inline_origins: Vec<InlineOriginRecord>,
+    /// All FUNC records, including their line and INLINE records.
+    pub functions: Vec<FuncRecord>,
+    /// All PUBLIC records.
+    pub publics: Vec<PublicRecord>,
+    /// All STACK CFI INIT records, including their STACK CFI records.
+    pub stack_cfi: Vec<StackCfiInitRecord>,
+    /// All STACK WIN records.
+    pub stack_win: Vec<StackWinRecord>,
+}
+
+impl SymbolFile {
+    /// Parses a Breakpad symbol file in the file system.
+    pub fn from_file<P: AsRef<Path>>(file_path: P) -> Result<SymbolFile> {
+        let buffer = utils::decompress_owned(utils::read_buffer(file_path)?)?;
+        Self::parse(buffer.as_slice())
+    }
+
+    /// Parses a buffer containing a Breakpad symbol file.
+    ///
+    /// Fails with a `SymbolFileError` describing the first line that cannot
+    /// be parsed. To skip invalid lines instead, iterate `Records`.
+    pub fn parse(buffer: &[u8]) -> Result<SymbolFile> {
+        let mut file = SymbolFile::default();
+        let mut records = Records::new(buffer);
+        while let Some(record) = records.next() {
+            let record = record.map_err(|d| SymbolFileError(d.to_string()))?;
+            let record_type = record.record_type();
+
+            if let Err(reason) = file.push(record) {
+                let diagnostic = ParseDiagnostic {
+                    line: records.line_number(),
+                    record_type,
+                    reason: reason.to_string(),
+                };
+                return Err(SymbolFileError(diagnostic.to_string()).into());
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Generates symbols from an ELF file with DWARF debug information.
+    ///
+    /// FUNC, line and FILE records are read from the DWARF sections, PUBLIC
+    /// records from the symbol table, and STACK CFI records from `.eh_frame`
+    /// and `.debug_frame`. The module is identified by its GNU build ID, and
+    /// `name` is written to the MODULE record. Use `write` to create a buffer
+    /// for `Resolver` or `FrameInfoMap`.
+    pub fn from_elf(buffer: &[u8], name: &str) -> Result<SymbolFile> {
+        elf::dump_symbols(buffer, name)
+    }
+
+    /// Generates symbols from a Mach-O file or universal binary.
+    ///
+    /// The file may be an executable, a library or the DWARF file in a dSYM
+    /// bundle. Universal binaries with more than one architecture require
+    /// `arch`, such as `"x86_64"` or `"arm64"`. The module is identified by its
+    /// `LC_UUID`, and `name` is written to the MODULE record.
+    ///
+    /// STACK CFI records are created from `__eh_frame` and `__unwind_info`,
+    /// which are not available in dSYM files. Use `from_macho_with_dsym` to
+    /// combine debug information from a dSYM with unwind information from
+    /// its binary.
+    pub fn from_macho(buffer: &[u8], name: &str, arch: Option<&str>) -> Result<SymbolFile> {
+        mach::dump_symbols(buffer, None, name, arch)
+    }
+
+    /// Generates symbols from a Mach-O binary and the DWARF file of its dSYM
+    /// bundle.
+    ///
+    /// FUNC and line records are read from the dSYM, all other records from
+    /// the binary. Fails if the two files do not have the same UUID.
+    pub fn from_macho_with_dsym(
+        binary: &[u8],
+        dsym: &[u8],
+        name: &str,
+        arch: Option<&str>,
+    ) -> Result<SymbolFile> {
+        mach::dump_symbols(binary, Some(dsym), name, arch)
+    }
+
+    /// Merges symbol files of the same module from different sources.
+    ///
+    /// All records are combined into a single file, for instance to add
+    /// STACK CFI records from one file to the functions of another. If records
+    /// of several files overlap, the file with precedence according to
+    /// `options` wins and the other record is reported in
+    /// `MergedSymbolFile::conflicts`. FILE and INLINE_ORIGIN records are
+    /// renumbered. Fails if the files declare different module identifiers.
+    pub fn merge(files: &[SymbolFile], options: &MergeOptions) -> Result<MergedSymbolFile> {
+        merge::merge(files, options)
+    }
+
+    /// Returns the STACK WIN record covering the given address, relative to
+    /// the code module.
+    ///
+    /// Like Breakpad's stackwalker, this prefers records with frame data over
+    /// FPO records. Records of other types are only returned if neither
+    /// exists. Use `ProgramEvaluator` to run the record's program string.
+    pub fn find_stack_win(&self, address: u64) -> Option<&StackWinRecord> {
+        let rank = |frame_type| match frame_type {
+            StackWinType::FrameData => 0,
+            StackWinType::Fpo => 1,
+            _ => 2,
+        };
+
+        self.stack_win
+            .iter()
+            .filter(|win| address >= win.address && address - win.address < win.size)
+            .min_by_key(|win| rank(win.frame_type))
+    }
+
+    /// Writes this file in Breakpad's text format.
+    ///
+    /// Records are written in canonical order: MODULE, INFO, FILE,
+    /// INLINE_ORIGIN, FUNC with its INLINE and line records, PUBLIC, STACK CFI
+    /// and STACK WIN. Files written by `dump_syms` use the same order, so they
+    /// are reproduced exactly.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<()> {
+        if let Some(ref module) = self.module {
+            writeln!(writer, "{}", module)?;
+        }
+
+        for info in &self.info {
+            writeln!(writer, "{}", info)?;
+        }
+
+        for file in &self.files {
+            writeln!(writer, "{}", file)?;
+        }
+
+        for origin in &self.inline_origins {
+            writeln!(writer, "{}", origin)?;
+        }
+
+        for function in &self.functions {
+            writeln!(writer, "{}", function)?;
+            for inline in &function.inlines {
+                writeln!(writer, "{}", inline)?;
+            }
+            for line in &function.lines {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+
+        for public in &self.publics {
+            writeln!(writer, "{}", public)?;
+        }
+
+        for cfi in &self.stack_cfi {
+            writeln!(writer, "{}", cfi)?;
+            for delta in &cfi.deltas {
+                writeln!(writer, "{}", delta)?;
+            }
+        }
+
+        for win in &self.stack_win {
+            writeln!(writer, "{}", win)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a record to this file. Line and INLINE records are attached to
+    /// the last FUNC record, and STACK CFI records to the last STACK CFI INIT
+    /// record.
+    fn push(&mut self, record: Record) -> ::std::result::Result<(), &'static str> {
+        match record {
+            Record::Module(module) => {
+                if self.module.is_some() {
+                    return Err("duplicate module record");
+                }
+                self.module = Some(module);
+            }
+            Record::Info(info) => self.info.push(info),
+            Record::File(file) => self.files.push(file),
+            Record::InlineOrigin(origin) => self.inline_origins.push(origin),
+            Record::Func(func) => self.functions.push(func),
+            Record::Inline(inline) => self.functions
+                .last_mut()
+                .ok_or("inline record without a function")?
+                .inlines
+                .push(inline),
+            Record::Line(line) => self.functions
+                .last_mut()
+                .ok_or("line record without a function")?
+                .lines
+                .push(line),
+            Record::Public(public) => self.publics.push(public),
+            Record::StackCfiInit(cfi) => self.stack_cfi.push(cfi),
+            Record::StackCfi(delta) => self.stack_cfi
+                .last_mut()
+                .ok_or("STACK CFI record without STACK CFI INIT")?
+                .deltas
+                .push(delta),
+            Record::StackWin(win) => self.stack_win.push(win),
+        }
+
+        Ok(())
+    }
+}
+
+/// A Breakpad symbol file split into unwind information and symbols.
+///
+/// Stack walking only requires STACK records, while symbolication requires
+/// everything else. Splitting a symbol file allows to cache both parts
+/// separately and to pass only the compact `frame_info` buffer into a
+/// `FrameInfoMap`. Both buffers start with the MODULE and INFO records of the
+/// original file, so they are valid symbol files on their own.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SplitSymbolFile {
+    /// MODULE, INFO, STACK CFI and STACK WIN records.
+    pub frame_info: Vec<u8>,
+    /// MODULE, INFO, FILE, INLINE_ORIGIN, FUNC, INLINE, line and PUBLIC
+    /// records.
+    pub symbols: Vec<u8>,
+}
+
+impl SplitSymbolFile {
+    /// Splits a buffer containing a Breakpad symbol file.
+    ///
+    /// Lines are only classified by their keyword and copied verbatim
+    /// without being parsed. Empty lines are dropped.
+    pub fn split(buffer: &[u8]) -> SplitSymbolFile {
+        let mut split = SplitSymbolFile::default();
+
+        for line in buffer.split(|&byte| byte == b'\n') {
+            let keyword = line.split(|&byte| byte == b' ').next().unwrap_or(&[]);
+            let targets: &mut [&mut Vec<u8>] = match keyword {
+                b"" | b"\r" => continue,
+                b"MODULE" | b"INFO" => &mut [&mut split.frame_info, &mut split.symbols],
+                b"STACK" => &mut [&mut split.frame_info],
+                _ => &mut [&mut split.symbols],
+            };
+
+            for target in targets {
+                target.extend_from_slice(line);
+                target.push(b'\n');
+            }
+        }
+
+        split
+    }
+}
+
+type RecordResult<T> = ::std::result::Result<T, &'static str>;
+
+/// Parses a single non-empty line of a symbol file.
+fn parse_record(line: &str) -> ::std::result::Result<Record, (RecordType, &'static str)> {
+    let (keyword, rest) = match line.find(' ') {
+        Some(index) => (&line[..index], &line[index + 1..]),
+        None => (line, ""),
+    };
+
+    let (record_type, result) = match keyword {
+        "MODULE" => (RecordType::Module, parse_module(rest)),
+        "INFO" => (RecordType::Info, parse_info(rest)),
+        "FILE" => (RecordType::File, parse_file(rest)),
+        "INLINE_ORIGIN" => (RecordType::InlineOrigin, parse_inline_origin(rest)),
+        "FUNC" => (RecordType::Func, parse_func(rest)),
+        "INLINE" => (RecordType::Inline, parse_inline(rest)),
+        "PUBLIC" => (RecordType::Public, parse_public(rest)),
+        "STACK" => (RecordType::Stack, parse_stack(rest)),
+        _ => (RecordType::Line, parse_line(line)),
+    };
+
+    result.map_err(|reason| (record_type, reason))
+}
+
+/// Splits a record into at most `count` whitespace separated fields. The last
+/// field contains the remainder of the line, which allows names to contain
+/// spaces. Returns `None` if there are fewer fields.
+fn split_fields(line: &str, count: usize) -> Option<Vec<&str>> {
+    let mut fields = Vec::with_capacity(count);
+    let mut rest = line.trim_start();
+
+    while fields.len() + 1 < count {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    fields.push(rest);
+    Some(fields)
+}
+
+fn parse_hex(field: &str) -> Option<u64> {
+    u64::from_str_radix(field, 16).ok()
+}
+
+fn parse_hex32(field: &str) -> Option<u32> {
+    u32::from_str_radix(field, 16).ok()
+}
+
+fn parse_dec(field: &str) -> Option<u32> {
+    field.parse().ok()
+}
+
+/// Strips the optional "m" marker of FUNC and PUBLIC records, which denotes
+/// that multiple symbols share this address.
+fn strip_multiple(rest: &str) -> (bool, &str) {
+    let rest = rest.trim_start();
+    match rest.strip_prefix("m ") {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    }
+}
+
+fn parse_module(rest: &str) -> RecordResult<Record> {
+    // MODULE <os> <arch> <id> <name>
+    let fields = split_fields(rest, 4).ok_or("missing fields")?;
+    Ok(Record::Module(ModuleRecord {
+        os: fields[0].to_string(),
+        arch: fields[1].to_string(),
+        id: CodeModuleId::parse(fields[2]).map_err(|_| "invalid module identifier")?,
+        name: fields[3].to_string(),
+    }))
+}
+
+fn parse_info(rest: &str) -> RecordResult<Record> {
+    // INFO CODE_ID <code id> [<code file>]
+    if rest.starts_with("CODE_ID ") {
+        let fields = split_fields(rest, 3)
+            .or_else(|| split_fields(rest, 2))
+            .ok_or("missing fields")?;
+
+        return Ok(Record::Info(InfoRecord::CodeId {
+            code_id: fields[1].to_string(),
+            code_file: fields.get(2).map(|file| file.to_string()),
+        }));
+    }
+
+    Ok(Record::Info(InfoRecord::Other(rest.to_string())))
+}
+
+fn parse_file(rest: &str) -> RecordResult<Record> {
+    // FILE <id> <name>
+    let fields = split_fields(rest, 2).ok_or("missing fields")?;
+    Ok(Record::File(FileRecord {
+        id: parse_dec(fields[0]).ok_or("invalid file identifier")?,
+        name: fields[1].to_string(),
+    }))
+}
+
+fn parse_inline_origin(rest: &str) -> RecordResult<Record> {
+    // INLINE_ORIGIN <id> [<file>] <name>
+    let fields = split_fields(rest, 2).ok_or("missing fields")?;
+    Ok(Record::InlineOrigin(InlineOriginRecord {
+        id: parse_dec(fields[0]).ok_or("invalid origin identifier")?,
+        name: fields[1].to_string(),
+    }))
+}
+
+fn parse_func(rest: &str) -> RecordResult<Record> {
+    // FUNC [m] <address> <size> <parameter size> <name>
+    let (multiple, rest) = strip_multiple(rest);
+    let fields = split_fields(rest, 4).ok_or("missing fields")?;
+    Ok(Record::Func(FuncRecord {
+        multiple,
+        address: parse_hex(fields[0]).ok_or("invalid address")?,
+        size: parse_hex(fields[1]).ok_or("invalid size")?,
+        parameter_size: parse_hex(fields[2]).ok_or("invalid parameter size")?,
+        name: fields[3].to_string(),
+        inlines: Vec::new(),
+        lines: Vec::new(),
+    }))
+}
+
+fn parse_inline(rest: &str) -> RecordResult<Record> {
+    // INLINE <depth> <call line> [<call file>] <origin> [<address> <size>]+
+    // Older versions of dump_syms omit the call site file, which results in
+    // an odd number of fields.
+    let fields: Vec<_> = rest.split_whitespace().collect();
+    if fields.len() < 5 {
+        return Err("missing fields");
+    }
+
+    let (call_file, origin, ranges) = if fields.len() % 2 == 1 {
+        (None, fields[2], &fields[3..])
+    } else {
+        (Some(fields[2]), fields[3], &fields[4..])
+    };
+
+    let call_file = match call_file {
+        Some(field) => Some(parse_dec(field).ok_or("invalid call site file")?),
+        None => None,
+    };
+
+    let ranges = ranges
+        .chunks(2)
+        .map(|range| Some((parse_hex(range[0])?, parse_hex(range[1])?)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or("invalid address range")?;
+
+    Ok(Record::Inline(InlineRecord {
+        depth: parse_dec(fields[0]).ok_or("invalid depth")?,
+        call_line: parse_dec(fields[1]).ok_or("invalid call site line")?,
+        call_file,
+        origin_id: parse_dec(origin).ok_or("invalid origin identifier")?,
+        ranges,
+    }))
+}
+
+fn parse_line(line: &str) -> RecordResult<Record> {
+    // <address> <size> <line> <file>
+    let fields: Vec<_> = line.split_whitespace().collect();
+    if fields.len() != 4 {
+        return Err("unknown record or wrong number of fields");
+    }
+
+    Ok(Record::Line(LineRecord {
+        address: parse_hex(fields[0]).ok_or("invalid address")?,
+        size: parse_hex(fields[1]).ok_or("invalid size")?,
+        line: parse_dec(fields[2]).ok_or("invalid line number")?,
+        file_id: parse_dec(fields[3]).ok_or("invalid file identifier")?,
+    }))
+}
+
+fn parse_public(rest: &str) -> RecordResult<Record> {
+    // PUBLIC [m] <address> <parameter size> <name>
+    let (multiple, rest) = strip_multiple(rest);
+    let fields = split_fields(rest, 3).ok_or("missing fields")?;
+    Ok(Record::Public(PublicRecord {
+        multiple,
+        address: parse_hex(fields[0]).ok_or("invalid address")?,
+        parameter_size: parse_hex(fields[1]).ok_or("invalid parameter size")?,
+        name: fields[2].to_string(),
+    }))
+}
+
+fn parse_stack(rest: &str) -> RecordResult<Record> {
+    let fields = split_fields(rest, 2).ok_or("missing fields")?;
+    match fields[0] {
+        "CFI" => parse_stack_cfi(fields[1]),
+        "WIN" => parse_stack_win(fields[1]),
+        _ => Err("unknown STACK record"),
+    }
+}
+
+fn parse_stack_cfi(rest: &str) -> RecordResult<Record> {
+    // STACK CFI INIT <address> <size> <rules>
+    // STACK CFI <address> <rules>
+    if let Some(init) = rest.strip_prefix("INIT ") {
+        let fields = split_fields(init, 3).ok_or("missing fields")?;
+        let address = parse_hex(fields[0]).ok_or("invalid address")?;
+        let size = parse_hex(fields[1]).ok_or("invalid size")?;
+        if !validate_cfi_rules(fields[2])?.contains(&".cfa") {
+            return Err("missing .cfa rule");
+        }
+
+        return Ok(Record::StackCfiInit(StackCfiInitRecord {
+            address,
+            size,
+            rules: fields[2].to_string(),
+            deltas: Vec::new(),
+        }));
+    }
+
+    let fields = split_fields(rest, 2).ok_or("missing fields")?;
+    let address = parse_hex(fields[0]).ok_or("invalid address")?;
+    validate_cfi_rules(fields[1])?;
+    Ok(Record::StackCfi(StackCfiRecord {
+        address,
+        rules: fields[1].to_string(),
+    }))
+}
+
+/// Checks that CFI rules consist of `<register>: <expression>` pairs, where
+/// each expression is a postfix expression yielding a single value. Returns
+/// the names of all registers, including `.cfa` and `.ra`.
+fn validate_cfi_rules(rules: &str) -> RecordResult<Vec<&str>> {
+    let mut registers = Vec::new();
+    // Number of values on the stack of the current expression, or `None`
+    // before the first register name.
+    let mut depth = None;
+
+    for token in rules.split_whitespace() {
+        if let Some(register) = token.strip_suffix(':') {
+            if depth.is_some_and(|depth| depth != 1) {
+                return Err("invalid CFI rule expression");
+            }
+
+            if register.is_empty() {
+                return Err("missing CFI rule register");
+            }
+
+            registers.push(register);
+            depth = Some(0);
+            continue;
+        }
+
+        let current = depth.ok_or("missing CFI rule register")?;
+        depth = Some(match token {
+            "+" | "-" | "*" | "/" | "%" | "@" if current >= 2 => current - 1,
+            "^" if current >= 1 => current,
+            "+" | "-" | "*" | "/" | "%" | "@" | "^" => {
+                return Err("invalid CFI rule expression");
+            }
+            _ => current + 1,
+        });
+    }
+
+    match depth {
+        Some(1) => Ok(registers),
+        Some(_) => Err("invalid CFI rule expression"),
+        None => Err("missing CFI rules"),
+    }
+}
+
+fn parse_stack_win(rest: &str) -> RecordResult<Record> {
+    // STACK WIN <type> <address> <size> <prologue size> <epilogue size>
+    //   <parameter size> <saved register size> <local size> <max stack size>
+    //   <has program string> <program string | allocates base pointer>
+    let fields = split_fields(rest, 11).ok_or("missing fields")?;
+    let frame_type = parse_hex32(fields[0])
+        .and_then(StackWinType::from_raw)
+        .ok_or("invalid frame type")?;
+
+    let (program_string, allocates_base_pointer) = match fields[9] {
+        "1" => (Some(fields[10].to_string()), false),
+        "0" => (None, fields[10] != "0"),
+        _ => return Err("invalid program string flag"),
+    };
+
+    Ok(Record::StackWin(StackWinRecord {
+        frame_type,
+        address: parse_hex(fields[1]).ok_or("invalid address")?,
+        size: parse_hex(fields[2]).ok_or("invalid size")?,
+        prologue_size: parse_hex32(fields[3]).ok_or("invalid prologue size")?,
+        epilogue_size: parse_hex32(fields[4]).ok_or("invalid epilogue size")?,
+        parameter_size: parse_hex32(fields[5]).ok_or("invalid parameter size")?,
+        saved_register_size: parse_hex32(fields[6]).ok_or("invalid saved register size")?,
+        local_size: parse_hex32(fields[7]).ok_or("invalid local size")?,
+        max_stack_size: parse_hex32(fields[8]).ok_or("invalid max stack size")?,
+        program_string,
+        allocates_base_pointer,
+    }))
+}
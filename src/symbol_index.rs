@@ -0,0 +1,687 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::str;
+
+use uuid::Uuid;
+
+use errors::ErrorKind::SymbolIndexError;
+use errors::Result;
+use processor::CodeModuleId;
+use symbol_file::{Record, Records, StackCfiInitRecord, StackCfiRecord};
+use symbols::{Function, Inlinee, Line, ModuleInfo, PublicSymbol, SourceLocation, SymbolLookup,
+              SymbolSource, Symbols};
+
+const MAGIC: &[u8; 4] = b"BPIX";
+const VERSION: u32 = 1;
+
+/// Marks an absent optional string or file reference.
+const NONE: u32 = 0xffff_ffff;
+
+const HEADER_SIZE: usize = 72 + SECTION_COUNT * 8;
+
+const STRINGS: usize = 0;
+const FILES: usize = 1;
+const ORIGINS: usize = 2;
+const FUNCTIONS: usize = 3;
+const LINES: usize = 4;
+const INLINEES: usize = 5;
+const RANGES: usize = 6;
+const PUBLICS: usize = 7;
+const CFI: usize = 8;
+const CFI_DELTAS: usize = 9;
+const SECTION_COUNT: usize = 10;
+
+/// Size of a single entry in each section. Strings are counted in bytes.
+const ENTRY_SIZES: [usize; SECTION_COUNT] = [1, 12, 12, 48, 24, 24, 16, 24, 32, 16];
+
+/// Offset and number of entries of a section in a symbol index.
+#[derive(Clone, Copy, Debug, Default)]
+struct Section {
+    offset: usize,
+    count: usize,
+}
+
+/// Location of all tables in a symbol index, as validated by
+/// `SymbolIndex::parse`.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct IndexLayout {
+    sections: [Section; SECTION_COUNT],
+    size: usize,
+}
+
+/// A compact binary representation of a Breakpad symbol file.
+///
+/// The index stores FUNC, line, INLINE and PUBLIC records sorted by address
+/// in fixed-size tables, along with a shared string table and the STACK CFI
+/// records. Opening an index only validates its header, so it can be read
+/// directly from a memory-mapped file without parsing the symbols first.
+/// STACK WIN records are not included.
+///
+/// Create an index from a text symbol file with `SymbolIndex::convert` and
+/// use `Resolver::from_index` to symbolicate frames with it.
+#[derive(Clone, Copy, Debug)]
+pub struct SymbolIndex<'a> {
+    data: &'a [u8],
+    layout: IndexLayout,
+}
+
+impl<'a> SymbolIndex<'a> {
+    /// Opens a symbol index created by `SymbolIndex::convert`.
+    ///
+    /// Fails with a `SymbolIndexError` if the buffer does not contain an index
+    /// of a supported version, or if its tables exceed the buffer.
+    pub fn parse(data: &'a [u8]) -> Result<SymbolIndex<'a>> {
+        if data.len() < HEADER_SIZE || &data[..4] != MAGIC {
+            return Err(SymbolIndexError("Invalid symbol index".into()).into());
+        }
+
+        if read_u32(data, 4) != VERSION {
+            return Err(SymbolIndexError("Unsupported symbol index version".into()).into());
+        }
+
+        let mut sections = [Section::default(); SECTION_COUNT];
+        let mut size = HEADER_SIZE;
+        for (index, section) in sections.iter_mut().enumerate() {
+            section.offset = read_u32(data, 72 + index * 8) as usize;
+            section.count = read_u32(data, 76 + index * 8) as usize;
+
+            let end = section.count
+                .checked_mul(ENTRY_SIZES[index])
+                .and_then(|size| size.checked_add(section.offset));
+            match end {
+                Some(end) if end <= data.len() => size = size.max(end),
+                _ => return Err(SymbolIndexError("Symbol index is truncated".into()).into()),
+            }
+        }
+
+        Ok(SymbolIndex {
+            data,
+            layout: IndexLayout { sections, size },
+        })
+    }
+
+    /// Opens a symbol index with the layout of a previous `parse`, without
+    /// validating it again. If the buffer no longer fits the layout, all
+    /// tables of the returned index are empty.
+    pub(crate) fn with_layout(data: &'a [u8], layout: IndexLayout) -> SymbolIndex<'a> {
+        let layout = if data.len() < layout.size {
+            IndexLayout::default()
+        } else {
+            layout
+        };
+
+        SymbolIndex { data, layout }
+    }
+
+    /// Returns the layout of this index, see `with_layout`.
+    pub(crate) fn layout(&self) -> IndexLayout {
+        self.layout
+    }
+
+    /// Converts a buffer containing a Breakpad symbol file into an index and
+    /// writes it to the given writer.
+    ///
+    /// Like `Resolver::from_buffer`, lines that cannot be parsed are skipped.
+    pub fn convert<W: Write>(buffer: &[u8], mut writer: W) -> Result<()> {
+        let symbols = Symbols::parse(buffer);
+        let cfi = read_cfi(buffer);
+        writer.write_all(&IndexWriter::default().write(&symbols, &cfi))?;
+        Ok(())
+    }
+
+    /// Returns information about the code module declared in the MODULE
+    /// record of the original symbol file.
+    pub fn module(&self) -> Option<ModuleInfo> {
+        if read_u32(self.data, 8) == 0 {
+            return None;
+        }
+
+        let mut uuid = [0u8; 16];
+        uuid.copy_from_slice(&self.data[16..32]);
+
+        Some(ModuleInfo {
+            os: self.string_at(32).unwrap_or("").to_string(),
+            arch: self.string_at(40).unwrap_or("").to_string(),
+            id: CodeModuleId::from_parts(Uuid::from_bytes(&uuid).ok()?, read_u32(self.data, 12)),
+            debug_file: self.string_at(48).unwrap_or("").to_string(),
+            code_id: self.string_at(56).map(str::to_string),
+            code_file: self.string_at(64).map(str::to_string),
+        })
+    }
+
+    /// Returns the name of the source file with the given FILE identifier.
+    pub fn file_name(&self, id: u32) -> Option<&'a str> {
+        let entry = self.find_id(FILES, id)?;
+        self.string_at_entry(FILES, entry, 4)
+    }
+
+    /// Returns the number of FILE records in this index.
+    pub(crate) fn file_count(&self) -> usize {
+        self.layout.sections[FILES].count
+    }
+
+    /// Returns the identifier and name of the file at the given position.
+    pub(crate) fn file_at(&self, index: usize) -> Option<(u32, &'a str)> {
+        let entry = self.entry(FILES, index)?;
+        Some((read_u32(entry, 0), self.string_ref(entry, 4)?))
+    }
+
+    /// Returns the number of FUNC records in this index.
+    pub(crate) fn function_count(&self) -> usize {
+        self.layout.sections[FUNCTIONS].count
+    }
+
+    /// Returns the function at the given position, including its line records
+    /// and inlined ranges.
+    pub(crate) fn function_at(&self, index: usize) -> Option<Function> {
+        let entry = self.entry(FUNCTIONS, index)?;
+
+        let lines = self.slice(LINES, read_u32(entry, 32), read_u32(entry, 36))?;
+        let lines = (0..lines.count)
+            .filter_map(|index| lines.entry(index))
+            .map(|line| Line {
+                address: read_u64(line, 0),
+                size: read_u64(line, 8),
+                line: read_u32(line, 16),
+                file: read_u32(line, 20),
+            })
+            .collect();
+
+        let inlinees = self.slice(INLINEES, read_u32(entry, 40), read_u32(entry, 44))?;
+        let inlinees = (0..inlinees.count)
+            .filter_map(|index| inlinees.entry(index))
+            .map(|inlinee| {
+                let ranges = self.slice(RANGES, read_u32(inlinee, 16), read_u32(inlinee, 20));
+                Inlinee {
+                    depth: read_u32(inlinee, 0),
+                    call_line: read_u32(inlinee, 4),
+                    call_file: match read_u32(inlinee, 8) {
+                        NONE => None,
+                        id => Some(id),
+                    },
+                    origin: read_u32(inlinee, 12),
+                    ranges: ranges.map_or(Vec::new(), |ranges| {
+                        (0..ranges.count)
+                            .filter_map(|index| ranges.entry(index))
+                            .map(|range| (read_u64(range, 0), read_u64(range, 8)))
+                            .collect()
+                    }),
+                }
+            })
+            .collect();
+
+        Some(Function {
+            address: read_u64(entry, 0),
+            size: read_u64(entry, 8),
+            parameter_size: read_u64(entry, 16),
+            name: self.string_ref(entry, 24).unwrap_or("").to_string(),
+            lines,
+            inlinees,
+        })
+    }
+
+    /// Returns the number of PUBLIC records in this index.
+    pub(crate) fn public_count(&self) -> usize {
+        self.layout.sections[PUBLICS].count
+    }
+
+    /// Returns the public symbol at the given position.
+    pub(crate) fn public_at(&self, index: usize) -> Option<PublicSymbol> {
+        let entry = self.entry(PUBLICS, index)?;
+        Some(PublicSymbol {
+            address: read_u64(entry, 0),
+            parameter_size: read_u64(entry, 8),
+            name: self.string_ref(entry, 16).unwrap_or("").to_string(),
+        })
+    }
+
+    /// Returns the STACK CFI INIT record covering the given address, along
+    /// with all of its STACK CFI records.
+    pub fn find_cfi(&self, address: u64) -> Option<StackCfiInitRecord> {
+        let index = self.nearest(CFI, address)?;
+        let entry = self.entry(CFI, index)?;
+        let size = read_u64(entry, 8);
+        if address - read_u64(entry, 0) >= size {
+            return None;
+        }
+
+        let start = read_u32(entry, 24) as usize;
+        let count = read_u32(entry, 28) as usize;
+        let deltas = (start..start + count)
+            .filter_map(|delta| {
+                let delta_entry = self.entry(CFI_DELTAS, delta)?;
+                Some(StackCfiRecord {
+                    address: read_u64(delta_entry, 0),
+                    rules: self.string_ref(delta_entry, 8)?.to_string(),
+                })
+            })
+            .collect();
+
+        Some(StackCfiInitRecord {
+            address: read_u64(entry, 0),
+            size,
+            rules: self.string_ref(entry, 16)?.to_string(),
+            deltas,
+        })
+    }
+
+    /// Looks up source information for an address relative to the start of
+    /// the code module, like `Symbols::lookup`.
+    pub(crate) fn lookup(&self, address: u64) -> Option<SymbolLookup<'a>> {
+        let function = self.nearest(FUNCTIONS, address);
+        if let Some(index) = function {
+            let entry = self.entry(FUNCTIONS, index)?;
+            if address - read_u64(entry, 0) < read_u64(entry, 8) {
+                return self.lookup_function(entry, address);
+            }
+        }
+
+        let public = self.entry(PUBLICS, self.nearest(PUBLICS, address)?)?;
+        let public_address = read_u64(public, 0);
+        if let Some(index) = function {
+            if read_u64(self.entry(FUNCTIONS, index)?, 0) >= public_address {
+                return None;
+            }
+        }
+
+        Some(SymbolLookup {
+            location: SourceLocation {
+                function_name: self.string_ref(public, 16).unwrap_or(""),
+                file: None,
+                line: None,
+            },
+            source: SymbolSource::Public,
+            function_address: public_address,
+            function_size: 0,
+            parameter_size: read_u64(public, 8),
+            line_address: None,
+            inlined: Vec::new(),
+        })
+    }
+
+    fn lookup_function(&self, function: &'a [u8], address: u64) -> Option<SymbolLookup<'a>> {
+        let lines = self.slice(LINES, read_u32(function, 32), read_u32(function, 36))?;
+        let line_record = match lines.nearest(address) {
+            Some(line) if address - read_u64(line, 0) < read_u64(line, 8) => Some(line),
+            _ => None,
+        };
+
+        let mut file = line_record.and_then(|line| self.file_name(read_u32(line, 20)));
+        let mut line = line_record.map(|line| read_u32(line, 16));
+
+        let inlinees = self.slice(INLINEES, read_u32(function, 40), read_u32(function, 44))?;
+        let mut covering = Vec::new();
+        for index in 0..inlinees.count {
+            let inlinee = inlinees.entry(index)?;
+            let ranges = self.slice(RANGES, read_u32(inlinee, 16), read_u32(inlinee, 20))?;
+            let contains = (0..ranges.count).any(|range| match ranges.entry(range) {
+                Some(range) => {
+                    let start = read_u64(range, 0);
+                    address >= start && address - start < read_u64(range, 8)
+                }
+                None => false,
+            });
+
+            if contains {
+                covering.push(inlinee);
+            }
+        }
+
+        // Walk from the innermost inlinee outwards, see `Symbols::lookup`.
+        covering.sort_by_key(|inlinee| read_u32(inlinee, 0));
+        let mut inlined = Vec::with_capacity(covering.len());
+        for inlinee in covering.iter().rev() {
+            let origin = read_u32(inlinee, 12);
+            inlined.push(SourceLocation {
+                function_name: self.origin_name(origin).unwrap_or(""),
+                file,
+                line,
+            });
+
+            file = match read_u32(inlinee, 8) {
+                NONE => None,
+                id => self.file_name(id),
+            };
+            line = Some(read_u32(inlinee, 4));
+        }
+
+        Some(SymbolLookup {
+            location: SourceLocation {
+                function_name: self.string_ref(function, 24).unwrap_or(""),
+                file,
+                line,
+            },
+            source: SymbolSource::Func,
+            function_address: read_u64(function, 0),
+            function_size: read_u64(function, 8),
+            parameter_size: read_u64(function, 16),
+            line_address: line_record.map(|line| read_u64(line, 0)),
+            inlined,
+        })
+    }
+
+    fn origin_name(&self, id: u32) -> Option<&'a str> {
+        let entry = self.find_id(ORIGINS, id)?;
+        self.string_at_entry(ORIGINS, entry, 4)
+    }
+
+    /// Returns the entry at the given position of a section.
+    fn entry(&self, section: usize, index: usize) -> Option<&'a [u8]> {
+        self.slice(section, 0, self.layout.sections[section].count as u32)?
+            .entry(index)
+    }
+
+    /// Returns a range of entries within a section.
+    fn slice(&self, section: usize, start: u32, count: u32) -> Option<Table<'a>> {
+        let size = ENTRY_SIZES[section];
+        let (start, count) = (start as usize, count as usize);
+        if start.checked_add(count)? > self.layout.sections[section].count {
+            return None;
+        }
+
+        let offset = self.layout.sections[section].offset + start * size;
+        Some(Table {
+            data: &self.data[offset..offset + count * size],
+            size,
+            count,
+        })
+    }
+
+    /// Returns the position of the entry with the highest address lower than
+    /// or equal to the given address. Entries start with their address.
+    fn nearest(&self, section: usize, address: u64) -> Option<usize> {
+        let table = self.slice(section, 0, self.layout.sections[section].count as u32)?;
+        table.nearest_index(address)
+    }
+
+    /// Returns the position of the entry with the given identifier. Entries
+    /// start with their identifier.
+    fn find_id(&self, section: usize, id: u32) -> Option<usize> {
+        let table = self.slice(section, 0, self.layout.sections[section].count as u32)?;
+        let index = table
+            .partition_point(|entry| read_u32(entry, 0) <= id)
+            .checked_sub(1)?;
+
+        if read_u32(table.entry(index)?, 0) == id {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    fn string_at_entry(&self, section: usize, index: usize, offset: usize) -> Option<&'a str> {
+        self.string_ref(self.entry(section, index)?, offset)
+    }
+
+    /// Reads a string reference from the header.
+    fn string_at(&self, offset: usize) -> Option<&'a str> {
+        self.string_ref(self.data, offset)
+    }
+
+    /// Resolves a string reference stored at the given offset of an entry.
+    fn string_ref(&self, entry: &[u8], offset: usize) -> Option<&'a str> {
+        let start = read_u32(entry, offset);
+        if start == NONE {
+            return None;
+        }
+
+        let (start, len) = (start as usize, read_u32(entry, offset + 4) as usize);
+        let strings = self.layout.sections[STRINGS];
+        if start.checked_add(len)? > strings.count {
+            return None;
+        }
+
+        let offset = strings.offset + start;
+        str::from_utf8(&self.data[offset..offset + len]).ok()
+    }
+}
+
+/// A range of fixed-size entries in a section.
+struct Table<'a> {
+    data: &'a [u8],
+    size: usize,
+    count: usize,
+}
+
+impl<'a> Table<'a> {
+    fn entry(&self, index: usize) -> Option<&'a [u8]> {
+        if index >= self.count {
+            return None;
+        }
+
+        Some(&self.data[index * self.size..(index + 1) * self.size])
+    }
+
+    /// Returns the number of leading entries matching the predicate. Entries
+    /// must be partitioned, so that all matching entries come first.
+    fn partition_point<F>(&self, predicate: F) -> usize
+    where
+        F: Fn(&[u8]) -> bool,
+    {
+        let (mut low, mut high) = (0, self.count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if predicate(&self.data[mid * self.size..(mid + 1) * self.size]) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        low
+    }
+
+    fn nearest_index(&self, address: u64) -> Option<usize> {
+        self.partition_point(|entry| read_u64(entry, 0) <= address)
+            .checked_sub(1)
+    }
+
+    fn nearest(&self, address: u64) -> Option<&'a [u8]> {
+        self.entry(self.nearest_index(address)?)
+    }
+}
+
+/// Reads all STACK CFI INIT records and their STACK CFI records, sorted by
+/// address. Invalid lines are skipped.
+fn read_cfi(buffer: &[u8]) -> Vec<StackCfiInitRecord> {
+    let mut records: Vec<StackCfiInitRecord> = Vec::new();
+    for record in Records::new(buffer) {
+        match record {
+            Ok(Record::StackCfiInit(cfi)) => records.push(cfi),
+            Ok(Record::StackCfi(delta)) => {
+                if let Some(cfi) = records.last_mut() {
+                    cfi.deltas.push(delta);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    records.sort_by_key(|record| record.address);
+    records
+}
+
+/// Serializes symbols into the binary format read by `SymbolIndex`.
+#[derive(Default)]
+struct IndexWriter<'s> {
+    strings: Vec<u8>,
+    string_refs: HashMap<&'s str, u32>,
+    sections: Vec<Vec<u8>>,
+}
+
+impl<'s> IndexWriter<'s> {
+    fn write(mut self, symbols: &'s Symbols, cfi: &'s [StackCfiInitRecord]) -> Vec<u8> {
+        self.sections = vec![Vec::new(); SECTION_COUNT];
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(MAGIC);
+        push_u32(&mut header, VERSION);
+        match symbols.module {
+            Some(ref module) => {
+                push_u32(&mut header, 1);
+                push_u32(&mut header, module.id.age());
+                header.extend_from_slice(module.id.uuid().as_bytes());
+                self.push_string(&mut header, Some(&module.os));
+                self.push_string(&mut header, Some(&module.arch));
+                self.push_string(&mut header, Some(&module.debug_file));
+                self.push_string(&mut header, module.code_id.as_deref());
+                self.push_string(&mut header, module.code_file.as_deref());
+            }
+            None => header.resize(72, 0),
+        }
+
+        for (&id, name) in &symbols.files {
+            self.push_named(FILES, id, name);
+        }
+
+        for (&id, name) in &symbols.inline_origins {
+            self.push_named(ORIGINS, id, name);
+        }
+
+        for function in &symbols.functions {
+            let lines_start = self.count(LINES);
+            for line in &function.lines {
+                let mut entry = Vec::with_capacity(ENTRY_SIZES[LINES]);
+                push_u64(&mut entry, line.address);
+                push_u64(&mut entry, line.size);
+                push_u32(&mut entry, line.line);
+                push_u32(&mut entry, line.file);
+                self.sections[LINES].extend_from_slice(&entry);
+            }
+
+            let inlinees_start = self.count(INLINEES);
+            for inlinee in &function.inlinees {
+                let ranges_start = self.count(RANGES);
+                for &(start, size) in &inlinee.ranges {
+                    let mut entry = Vec::with_capacity(ENTRY_SIZES[RANGES]);
+                    push_u64(&mut entry, start);
+                    push_u64(&mut entry, size);
+                    self.sections[RANGES].extend_from_slice(&entry);
+                }
+
+                let mut entry = Vec::with_capacity(ENTRY_SIZES[INLINEES]);
+                push_u32(&mut entry, inlinee.depth);
+                push_u32(&mut entry, inlinee.call_line);
+                push_u32(&mut entry, inlinee.call_file.unwrap_or(NONE));
+                push_u32(&mut entry, inlinee.origin);
+                push_u32(&mut entry, ranges_start);
+                push_u32(&mut entry, inlinee.ranges.len() as u32);
+                self.sections[INLINEES].extend_from_slice(&entry);
+            }
+
+            let mut entry = Vec::with_capacity(ENTRY_SIZES[FUNCTIONS]);
+            push_u64(&mut entry, function.address);
+            push_u64(&mut entry, function.size);
+            push_u64(&mut entry, function.parameter_size);
+            self.push_string(&mut entry, Some(&function.name));
+            push_u32(&mut entry, lines_start);
+            push_u32(&mut entry, function.lines.len() as u32);
+            push_u32(&mut entry, inlinees_start);
+            push_u32(&mut entry, function.inlinees.len() as u32);
+            self.sections[FUNCTIONS].extend_from_slice(&entry);
+        }
+
+        for public in &symbols.publics {
+            let mut entry = Vec::with_capacity(ENTRY_SIZES[PUBLICS]);
+            push_u64(&mut entry, public.address);
+            push_u64(&mut entry, public.parameter_size);
+            self.push_string(&mut entry, Some(&public.name));
+            self.sections[PUBLICS].extend_from_slice(&entry);
+        }
+
+        for record in cfi {
+            let deltas_start = self.count(CFI_DELTAS);
+            for delta in &record.deltas {
+                let mut entry = Vec::with_capacity(ENTRY_SIZES[CFI_DELTAS]);
+                push_u64(&mut entry, delta.address);
+                self.push_string(&mut entry, Some(&delta.rules));
+                self.sections[CFI_DELTAS].extend_from_slice(&entry);
+            }
+
+            let mut entry = Vec::with_capacity(ENTRY_SIZES[CFI]);
+            push_u64(&mut entry, record.address);
+            push_u64(&mut entry, record.size);
+            self.push_string(&mut entry, Some(&record.rules));
+            push_u32(&mut entry, deltas_start);
+            push_u32(&mut entry, record.deltas.len() as u32);
+            self.sections[CFI].extend_from_slice(&entry);
+        }
+
+        self.sections[STRINGS] = self.strings;
+
+        // Align all tables to eight bytes, so that the index can be read
+        // efficiently from memory-mapped files.
+        let mut output = header;
+        let mut tables = Vec::new();
+        let mut offset = HEADER_SIZE;
+        for (index, section) in self.sections.iter().enumerate() {
+            push_u32(&mut output, offset as u32);
+            push_u32(&mut output, (section.len() / ENTRY_SIZES[index]) as u32);
+            tables.extend_from_slice(section);
+            offset += section.len();
+            while !offset.is_multiple_of(8) {
+                tables.push(0);
+                offset += 1;
+            }
+        }
+
+        output.extend_from_slice(&tables);
+        output
+    }
+
+    fn count(&self, section: usize) -> u32 {
+        (self.sections[section].len() / ENTRY_SIZES[section]) as u32
+    }
+
+    fn push_named(&mut self, section: usize, id: u32, name: &'s str) {
+        let mut entry = Vec::with_capacity(ENTRY_SIZES[section]);
+        push_u32(&mut entry, id);
+        self.push_string(&mut entry, Some(name));
+        self.sections[section].extend_from_slice(&entry);
+    }
+
+    /// Appends a reference to the string, adding it to the string table
+    /// unless an equal string has been added before.
+    fn push_string(&mut self, entry: &mut Vec<u8>, string: Option<&'s str>) {
+        let string = match string {
+            Some(string) => string,
+            None => {
+                push_u32(entry, NONE);
+                push_u32(entry, 0);
+                return;
+            }
+        };
+
+        let strings = &mut self.strings;
+        let offset = *self.string_refs.entry(string).or_insert_with(|| {
+            let offset = strings.len() as u32;
+            strings.extend_from_slice(string.as_bytes());
+            offset
+        });
+
+        push_u32(entry, offset);
+        push_u32(entry, string.len() as u32);
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    data[offset..offset + 4]
+        .iter()
+        .rev()
+        .fold(0, |value, &byte| value << 8 | u32::from(byte))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from(read_u32(data, offset)) | u64::from(read_u32(data, offset + 4)) << 32
+}
+
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    for shift in 0..4 {
+        data.push((value >> (shift * 8)) as u8);
+    }
+}
+
+fn push_u64(data: &mut Vec<u8>, value: u64) {
+    push_u32(data, value as u32);
+    push_u32(data, (value >> 32) as u32);
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use errors::Result;
+use processor::{CodeModule, CodeModuleId, FrameInfoMap, FrameTrust, ProcessState, StackFrame};
+use resolver::{Resolver, SymbolCache, SymbolInfo};
+use utils;
+
+/// A source of symbols for the code modules of a process.
+///
+/// Implementors return the `Resolver` holding the symbols of a code module.
+/// This is implemented by `SymbolCache` for multiple modules, and by
+/// `Resolver` itself for the single module declared in its symbol file.
+pub trait SymbolProvider {
+    /// Returns the resolver for the given code module, or `None` if no
+    /// symbols are available for it.
+    fn resolver(&self, module: &CodeModule) -> Option<&Resolver>;
+}
+
+impl SymbolProvider for SymbolCache {
+    fn resolver(&self, module: &CodeModule) -> Option<&Resolver> {
+        self.get(&module.try_id()?)
+    }
+}
+
+impl SymbolProvider for Resolver {
+    fn resolver(&self, module: &CodeModule) -> Option<&Resolver> {
+        match self.module() {
+            Some(info) if Some(info.id) == module.try_id() => Some(self),
+            _ => None,
+        }
+    }
+}
+
+/// An owned copy of a `CodeModule` referenced by a symbolicated process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolicatedModule {
+    /// Unique identifier of the code module. `None` if the module's debug
+    /// identifier is empty or malformed.
+    pub id: Option<CodeModuleId>,
+    /// Address at which the module was loaded into the process.
+    pub base_address: u64,
+    /// Size of the module in memory.
+    pub size: u64,
+    /// Path or file name of the executable or library.
+    pub code_file: String,
+    /// Path or file name of the file containing debug information.
+    pub debug_file: String,
+}
+
+impl<'a> From<&'a CodeModule> for SymbolicatedModule {
+    fn from(module: &'a CodeModule) -> SymbolicatedModule {
+        SymbolicatedModule {
+            id: module.try_id(),
+            base_address: module.base_address(),
+            size: module.size(),
+            code_file: module.code_file(),
+            debug_file: module.debug_file(),
+        }
+    }
+}
+
+/// A stack frame with symbol information.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolicatedFrame {
+    /// Absolute address of the frame's instruction. See
+    /// `StackFrame::instruction`.
+    pub instruction: u64,
+    /// How well the instruction pointer is trusted.
+    pub trust: FrameTrust,
+    /// The code module containing the instruction, if any.
+    pub module: Option<SymbolicatedModule>,
+    /// Symbol information for the instruction. `None` if the instruction is
+    /// not in a code module, or no symbols were found for it.
+    pub symbol: Option<SymbolInfo>,
+}
+
+/// The stack of a single thread with symbol information.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolicatedThread {
+    /// Identifier of the thread.
+    pub thread_id: u32,
+    /// Frames of the thread's stack, innermost first.
+    pub frames: Vec<SymbolicatedFrame>,
+}
+
+/// An owned, symbolicated copy of all call stacks in a `ProcessState`.
+///
+/// Unlike `ResolvedStackFrame`, this does not borrow from the `ProcessState`
+/// and can be kept after the state has been dropped.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolicatedProcess {
+    /// All threads of the process, in the same order as
+    /// `ProcessState::threads`.
+    pub threads: Vec<SymbolicatedThread>,
+    /// All code modules referenced by frames of any thread, ordered by their
+    /// base address.
+    pub modules: Vec<SymbolicatedModule>,
+}
+
+impl SymbolicatedProcess {
+    /// Symbolicates all frames of the given `ProcessState` with symbols from
+    /// the provider.
+    ///
+    /// Frames are looked up by their instruction address. Names added to
+    /// frames during stackwalking are not carried over.
+    pub fn from_process_state<P>(state: &ProcessState, provider: &P) -> SymbolicatedProcess
+    where
+        P: SymbolProvider + ?Sized,
+    {
+        let threads = state
+            .threads()
+            .iter()
+            .map(|stack| SymbolicatedThread {
+                thread_id: stack.thread_id(),
+                frames: stack
+                    .frames()
+                    .iter()
+                    .map(|frame| symbolicate_frame(frame, provider))
+                    .collect(),
+            })
+            .collect();
+
+        let mut modules: Vec<_> = state
+            .referenced_modules()
+            .into_iter()
+            .map(SymbolicatedModule::from)
+            .collect();
+        modules.sort_by_key(|module| module.base_address);
+
+        SymbolicatedProcess { threads, modules }
+    }
+
+    /// Reads a minidump from the filesystem into memory and symbolicates it
+    ///
+    /// See `from_minidump_buffer` for more information.
+    pub fn from_minidump_file<P: AsRef<Path>>(
+        file_path: P,
+        symbols: &FrameInfoMap,
+    ) -> Result<SymbolicatedProcess> {
+        let buffer = utils::read_buffer(file_path)?;
+        Self::from_minidump_buffer(buffer.as_slice(), symbols)
+    }
+
+    /// Processes a minidump and symbolicates it with the same symbols
+    ///
+    /// The map contains complete Breakpad symbol files, including FUNC, line
+    /// and PUBLIC records as well as STACK CFI and STACK WIN records. The
+    /// stackwalker uses all of them to find frames, and every frame is then
+    /// named with a `Resolver` created from the same symbols. This yields a
+    /// symbolicated process from a single call.
+    pub fn from_minidump_buffer(
+        buffer: &[u8],
+        symbols: &FrameInfoMap,
+    ) -> Result<SymbolicatedProcess> {
+        let state = ProcessState::from_minidump_buffer(buffer, Some(symbols))?;
+
+        let mut cache = SymbolCache::new();
+        for (id, data) in symbols {
+            cache.insert(*id, Resolver::from_buffer(data)?);
+        }
+
+        Ok(Self::from_process_state(&state, &cache))
+    }
+}
+
+fn symbolicate_frame<P>(frame: &StackFrame, provider: &P) -> SymbolicatedFrame
+where
+    P: SymbolProvider + ?Sized,
+{
+    let module = frame.module();
+    let symbol = module.and_then(|module| {
+        let address = frame.instruction().checked_sub(module.base_address())?;
+        provider.resolver(module)?.lookup(address)
+    });
+
+    SymbolicatedFrame {
+        instruction: frame.instruction(),
+        trust: frame.trust(),
+        module: module.map(SymbolicatedModule::from),
+        symbol,
+    }
+}
@@ -0,0 +1,776 @@
+use std::borrow::Cow;
+use std::collections::{btree_map, BTreeMap};
+use std::{mem, slice, str};
+
+use processor::CodeModuleId;
+use symbol_file::{InfoRecord, ParseDiagnostic, Record, RecordType, Records};
+use symbol_index::SymbolIndex;
+
+/// Information about the code module declared in the header of a symbol file.
+///
+/// This is read from the `MODULE <os> <arch> <id> <name>` record, and the
+/// optional `INFO CODE_ID <code id> [<code file>]` record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleInfo {
+    /// Operating system the module was built for, e.g. `mac` or `windows`.
+    pub os: String,
+    /// CPU architecture of the module, e.g. `x86_64`.
+    pub arch: String,
+    /// Identifier of the module, matching `CodeModule::id`.
+    pub id: CodeModuleId,
+    /// Name of the file containing debug information, matching
+    /// `CodeModule::debug_file`.
+    pub debug_file: String,
+    /// Identifier of the executable or library, if declared.
+    pub code_id: Option<String>,
+    /// Name of the executable or library, if declared.
+    pub code_file: Option<String>,
+}
+
+/// A source line record, mapping a range of instructions in a function to a
+/// line in a source file.
+#[derive(Clone, Debug)]
+pub struct Line {
+    pub(crate) address: u64,
+    pub(crate) size: u64,
+    pub(crate) line: u32,
+    pub(crate) file: u32,
+}
+
+impl Line {
+    /// Start address of the line record, relative to the code module.
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Number of bytes covered by this line record.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Line number in the source file.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Identifier of the FILE record declaring the source file.
+    pub fn file_id(&self) -> u32 {
+        self.file
+    }
+
+    fn contains(&self, address: u64) -> bool {
+        address >= self.address && address - self.address < self.size
+    }
+}
+
+/// A range of instructions inlined into a function from an inline origin.
+#[derive(Clone, Debug)]
+pub(crate) struct Inlinee {
+    pub depth: u32,
+    pub call_line: u32,
+    pub call_file: Option<u32>,
+    pub origin: u32,
+    pub ranges: Vec<(u64, u64)>,
+}
+
+impl Inlinee {
+    fn contains(&self, address: u64) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(start, size)| address >= start && address - start < size)
+    }
+}
+
+/// A function declared by a FUNC record, including its line records and
+/// inlined ranges.
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub(crate) address: u64,
+    pub(crate) size: u64,
+    pub(crate) parameter_size: u64,
+    pub(crate) name: String,
+    pub(crate) lines: Vec<Line>,
+    pub(crate) inlinees: Vec<Inlinee>,
+}
+
+impl Function {
+    /// Start address of the function, relative to the code module.
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Number of bytes covered by this function.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Size of the parameters passed to the function on the stack.
+    pub fn parameter_size(&self) -> u64 {
+        self.parameter_size
+    }
+
+    /// Name of the function, as written to the symbol file.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns an iterator over all line records of this function, ordered
+    /// by address.
+    pub fn lines(&self) -> Lines<'_> {
+        Lines(self.lines.iter())
+    }
+
+    fn contains(&self, address: u64) -> bool {
+        address >= self.address && address - self.address < self.size
+    }
+
+    /// Returns the line record covering the given address.
+    fn find_line(&self, address: u64) -> Option<&Line> {
+        let index = match self.lines.binary_search_by_key(&address, |l| l.address) {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+
+        Some(&self.lines[index]).filter(|line| line.contains(address))
+    }
+
+    /// Returns all inlinees covering the given address, outermost first.
+    fn find_inlinees(&self, address: u64) -> Vec<&Inlinee> {
+        let mut inlinees: Vec<_> = self.inlinees
+            .iter()
+            .filter(|inlinee| inlinee.contains(address))
+            .collect();
+
+        inlinees.sort_by_key(|inlinee| inlinee.depth);
+        inlinees
+    }
+}
+
+/// A public symbol declared by a PUBLIC record.
+#[derive(Clone, Debug)]
+pub struct PublicSymbol {
+    pub(crate) address: u64,
+    pub(crate) parameter_size: u64,
+    pub(crate) name: String,
+}
+
+impl PublicSymbol {
+    /// Address of the symbol, relative to the code module.
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Size of the parameters passed to the function on the stack.
+    pub fn parameter_size(&self) -> u64 {
+        self.parameter_size
+    }
+
+    /// Name of the symbol, as written to the symbol file.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Iterator over the functions of a symbol file, ordered by address.
+///
+/// Functions of a `SymbolIndex` are read from the index while iterating, so
+/// the iterator yields owned values for them.
+#[derive(Clone, Debug)]
+pub struct Functions<'a>(FunctionsInner<'a>);
+
+#[derive(Clone, Debug)]
+enum FunctionsInner<'a> {
+    Symbols(slice::Iter<'a, Function>),
+    Index(SymbolIndex<'a>, usize),
+}
+
+impl<'a> Functions<'a> {
+    pub(crate) fn from_index(index: SymbolIndex<'a>) -> Functions<'a> {
+        Functions(FunctionsInner::Index(index, 0))
+    }
+}
+
+impl<'a> Iterator for Functions<'a> {
+    type Item = Cow<'a, Function>;
+
+    fn next(&mut self) -> Option<Cow<'a, Function>> {
+        match self.0 {
+            FunctionsInner::Symbols(ref mut iter) => iter.next().map(Cow::Borrowed),
+            FunctionsInner::Index(ref index, ref mut position) => {
+                let function = index.function_at(*position)?;
+                *position += 1;
+                Some(Cow::Owned(function))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.0 {
+            FunctionsInner::Symbols(ref iter) => iter.size_hint(),
+            FunctionsInner::Index(ref index, position) => {
+                let remaining = index.function_count().saturating_sub(position);
+                (remaining, Some(remaining))
+            }
+        }
+    }
+}
+
+/// Iterator over the line records of a function, ordered by address.
+#[derive(Clone, Debug)]
+pub struct Lines<'a>(slice::Iter<'a, Line>);
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a Line;
+
+    fn next(&mut self) -> Option<&'a Line> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterator over the public symbols of a symbol file, ordered by address.
+///
+/// Like `Functions`, this yields owned values for symbols read from a
+/// `SymbolIndex`.
+#[derive(Clone, Debug)]
+pub struct PublicSymbols<'a>(PublicSymbolsInner<'a>);
+
+#[derive(Clone, Debug)]
+enum PublicSymbolsInner<'a> {
+    Symbols(slice::Iter<'a, PublicSymbol>),
+    Index(SymbolIndex<'a>, usize),
+}
+
+impl<'a> PublicSymbols<'a> {
+    pub(crate) fn from_index(index: SymbolIndex<'a>) -> PublicSymbols<'a> {
+        PublicSymbols(PublicSymbolsInner::Index(index, 0))
+    }
+}
+
+impl<'a> Iterator for PublicSymbols<'a> {
+    type Item = Cow<'a, PublicSymbol>;
+
+    fn next(&mut self) -> Option<Cow<'a, PublicSymbol>> {
+        match self.0 {
+            PublicSymbolsInner::Symbols(ref mut iter) => iter.next().map(Cow::Borrowed),
+            PublicSymbolsInner::Index(ref index, ref mut position) => {
+                let public = index.public_at(*position)?;
+                *position += 1;
+                Some(Cow::Owned(public))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.0 {
+            PublicSymbolsInner::Symbols(ref iter) => iter.size_hint(),
+            PublicSymbolsInner::Index(ref index, position) => {
+                let remaining = index.public_count().saturating_sub(position);
+                (remaining, Some(remaining))
+            }
+        }
+    }
+}
+
+/// Iterator over the FILE records of a symbol file, yielding identifiers and
+/// file names ordered by identifier.
+#[derive(Clone, Debug)]
+pub struct Files<'a>(FilesInner<'a>);
+
+#[derive(Clone, Debug)]
+enum FilesInner<'a> {
+    Symbols(btree_map::Iter<'a, u32, String>),
+    Index(SymbolIndex<'a>, usize),
+}
+
+impl<'a> Files<'a> {
+    pub(crate) fn from_index(index: SymbolIndex<'a>) -> Files<'a> {
+        Files(FilesInner::Index(index, 0))
+    }
+}
+
+impl<'a> Iterator for Files<'a> {
+    type Item = (u32, &'a str);
+
+    fn next(&mut self) -> Option<(u32, &'a str)> {
+        match self.0 {
+            FilesInner::Symbols(ref mut iter) => {
+                iter.next().map(|(id, name)| (*id, name.as_str()))
+            }
+            FilesInner::Index(ref index, ref mut position) => {
+                let file = index.file_at(*position)?;
+                *position += 1;
+                Some(file)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.0 {
+            FilesInner::Symbols(ref iter) => iter.size_hint(),
+            FilesInner::Index(ref index, position) => {
+                let remaining = index.file_count().saturating_sub(position);
+                (remaining, Some(remaining))
+            }
+        }
+    }
+}
+
+/// The kind of symbol record that was used to resolve an address.
+///
+/// Names from PUBLIC records are a best guess: a PUBLIC record only declares
+/// its start address, so the name may belong to an unrelated exported symbol
+/// preceding the actual function.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SymbolSource {
+    /// The address is covered by a FUNC record.
+    Func,
+    /// The address was attributed to the nearest preceding PUBLIC record.
+    Public,
+    /// No symbol was found for the address.
+    None,
+}
+
+/// A source location within a function.
+#[derive(Clone, Debug)]
+pub(crate) struct SourceLocation<'a> {
+    pub function_name: &'a str,
+    pub file: Option<&'a str>,
+    pub line: Option<u32>,
+}
+
+/// The result of an address lookup in `Symbols`.
+#[derive(Clone, Debug)]
+pub(crate) struct SymbolLookup<'a> {
+    /// Location in the outermost function. If the address was inlined, this
+    /// points to the call site of the outermost inlined function.
+    pub location: SourceLocation<'a>,
+    /// The kind of record that matched the address.
+    pub source: SymbolSource,
+    /// Start address of the FUNC or PUBLIC record.
+    pub function_address: u64,
+    /// Size of the FUNC record, or zero for PUBLIC records.
+    pub function_size: u64,
+    /// Size of the parameters passed to the function on the stack.
+    pub parameter_size: u64,
+    /// Start address of the line record covering the address.
+    pub line_address: Option<u64>,
+    /// Locations of inlined functions, innermost first.
+    pub inlined: Vec<SourceLocation<'a>>,
+}
+
+/// Parsed contents of a Breakpad symbol file, used for source line lookups.
+///
+/// Only FILE, FUNC, line, PUBLIC, INLINE_ORIGIN and INLINE records are read.
+/// Lines that cannot be parsed are skipped and recorded as diagnostics.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Symbols {
+    pub module: Option<ModuleInfo>,
+    pub files: BTreeMap<u32, String>,
+    pub inline_origins: BTreeMap<u32, String>,
+    pub functions: Vec<Function>,
+    pub publics: Vec<PublicSymbol>,
+    pub diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl Symbols {
+    /// Parses symbols from a buffer containing a Breakpad symbol file.
+    pub fn parse(buffer: &[u8]) -> Symbols {
+        let mut parser = Parser::default();
+        let mut records = Records::new(buffer);
+        while let Some(result) = records.next() {
+            match result {
+                Ok(record) => parser.push(records.line_number(), record),
+                Err(diagnostic) => parser.symbols.diagnostics.push(diagnostic),
+            }
+        }
+
+        parser.finish()
+    }
+
+    /// Returns the name of the source file with the given FILE identifier.
+    pub fn file_name(&self, id: u32) -> Option<&str> {
+        self.files.get(&id).map(String::as_str)
+    }
+
+    /// Returns an iterator over all FILE records.
+    pub fn files(&self) -> Files<'_> {
+        Files(FilesInner::Symbols(self.files.iter()))
+    }
+
+    /// Returns an iterator over all FUNC records.
+    pub fn functions(&self) -> Functions<'_> {
+        Functions(FunctionsInner::Symbols(self.functions.iter()))
+    }
+
+    /// Returns an iterator over all PUBLIC records.
+    pub fn publics(&self) -> PublicSymbols<'_> {
+        PublicSymbols(PublicSymbolsInner::Symbols(self.publics.iter()))
+    }
+
+    /// Returns the name of the inline origin with the given identifier.
+    fn origin_name(&self, id: u32) -> &str {
+        self.inline_origins
+            .get(&id)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    /// Returns the function covering the given address.
+    fn find_function(&self, address: u64) -> Option<&Function> {
+        self.nearest_function(address)
+            .filter(|function| function.contains(address))
+    }
+
+    /// Returns the function with the highest start address lower than or
+    /// equal to the given address, even if it does not cover the address.
+    fn nearest_function(&self, address: u64) -> Option<&Function> {
+        match self.functions
+            .binary_search_by_key(&address, |f| f.address)
+        {
+            Ok(index) => Some(&self.functions[index]),
+            Err(0) => None,
+            Err(index) => Some(&self.functions[index - 1]),
+        }
+    }
+
+    /// Returns the public symbol with the highest address lower than or equal
+    /// to the given address.
+    fn nearest_public(&self, address: u64) -> Option<&PublicSymbol> {
+        match self.publics.binary_search_by_key(&address, |p| p.address) {
+            Ok(index) => Some(&self.publics[index]),
+            Err(0) => None,
+            Err(index) => Some(&self.publics[index - 1]),
+        }
+    }
+
+    /// Looks up source information for an address relative to the start of
+    /// the code module.
+    ///
+    /// This mirrors the lookup of Breakpad's `BasicSourceLineResolver`: if no
+    /// FUNC record covers the address, the nearest PUBLIC record is used,
+    /// unless there is a FUNC record between it and the address.
+    pub fn lookup(&self, address: u64) -> Option<SymbolLookup<'_>> {
+        if let Some(function) = self.find_function(address) {
+            return Some(self.lookup_function(function, address));
+        }
+
+        let public = self.nearest_public(address)?;
+        if let Some(function) = self.nearest_function(address) {
+            if function.address >= public.address {
+                return None;
+            }
+        }
+
+        Some(SymbolLookup {
+            location: SourceLocation {
+                function_name: &public.name,
+                file: None,
+                line: None,
+            },
+            source: SymbolSource::Public,
+            function_address: public.address,
+            function_size: 0,
+            parameter_size: public.parameter_size,
+            line_address: None,
+            inlined: Vec::new(),
+        })
+    }
+
+    fn lookup_function<'a>(&'a self, function: &'a Function, address: u64) -> SymbolLookup<'a> {
+        let line_record = function.find_line(address);
+        let mut file = line_record.and_then(|line| self.file_name(line.file));
+        let mut line = line_record.map(|line| line.line);
+
+        // Each inlinee is called from its parent. Walk from the innermost
+        // inlinee outwards, so that every caller receives the call site of the
+        // function it inlined.
+        let inlinees = function.find_inlinees(address);
+        let mut inlined = Vec::with_capacity(inlinees.len());
+        for inlinee in inlinees.iter().rev() {
+            inlined.push(SourceLocation {
+                function_name: self.origin_name(inlinee.origin),
+                file,
+                line,
+            });
+
+            file = inlinee.call_file.and_then(|id| self.file_name(id));
+            line = Some(inlinee.call_line);
+        }
+
+        SymbolLookup {
+            location: SourceLocation {
+                function_name: &function.name,
+                file,
+                line,
+            },
+            source: SymbolSource::Func,
+            function_address: function.address,
+            function_size: function.size,
+            parameter_size: function.parameter_size,
+            line_address: line_record.map(|line| line.address),
+            inlined,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    symbols: Symbols,
+    /// Line number and unparsed remainder of INLINE_ORIGIN records after the
+    /// identifier. Its format depends on the format of INLINE records.
+    origins: Vec<(usize, u32, String)>,
+    /// Whether INLINE records omit the call site file, in which case
+    /// INLINE_ORIGIN records contain a file identifier instead.
+    legacy_inlines: bool,
+    /// Code identifier and file from the INFO CODE_ID record, which is added
+    /// to the module info after parsing.
+    code_id: Option<String>,
+    code_file: Option<String>,
+}
+
+impl Parser {
+    fn reject(&mut self, line: usize, record_type: RecordType, reason: &str) {
+        self.symbols.diagnostics.push(ParseDiagnostic {
+            line,
+            record_type,
+            reason: reason.to_string(),
+        });
+    }
+
+    fn push(&mut self, line_number: usize, record: Record) {
+        let record_type = record.record_type();
+        if let Err(reason) = self.push_record(line_number, record) {
+            self.reject(line_number, record_type, reason);
+        }
+    }
+
+    fn push_record(&mut self, line_number: usize, record: Record) -> Result<(), &'static str> {
+        match record {
+            Record::Module(module) => {
+                if self.symbols.module.is_some() {
+                    return Err("duplicate module record");
+                }
+
+                self.symbols.module = Some(ModuleInfo {
+                    os: module.os,
+                    arch: module.arch,
+                    id: module.id,
+                    debug_file: module.name,
+                    code_id: None,
+                    code_file: None,
+                });
+            }
+            Record::Info(InfoRecord::CodeId { code_id, code_file }) => {
+                self.code_id = Some(code_id);
+                self.code_file = code_file;
+            }
+            Record::File(file) => {
+                self.symbols.files.insert(file.id, file.name);
+            }
+            Record::InlineOrigin(origin) => {
+                self.origins.push((line_number, origin.id, origin.name));
+            }
+            Record::Func(func) => self.symbols.functions.push(Function {
+                address: func.address,
+                size: func.size,
+                parameter_size: func.parameter_size,
+                name: func.name,
+                lines: Vec::new(),
+                inlinees: Vec::new(),
+            }),
+            Record::Inline(inline) => {
+                let function = self.symbols
+                    .functions
+                    .last_mut()
+                    .ok_or("inline record without a function")?;
+
+                self.legacy_inlines |= inline.call_file.is_none();
+                function.inlinees.push(Inlinee {
+                    depth: inline.depth,
+                    call_line: inline.call_line,
+                    call_file: inline.call_file,
+                    origin: inline.origin_id,
+                    ranges: inline.ranges,
+                });
+            }
+            Record::Line(line) => {
+                let function = self.symbols
+                    .functions
+                    .last_mut()
+                    .ok_or("line record without a function")?;
+
+                function.lines.push(Line {
+                    address: line.address,
+                    size: line.size,
+                    line: line.line,
+                    file: line.file_id,
+                });
+            }
+            Record::Public(public) => self.symbols.publics.push(PublicSymbol {
+                address: public.address,
+                parameter_size: public.parameter_size,
+                name: public.name,
+            }),
+            Record::Info(_)
+            | Record::StackCfiInit(_)
+            | Record::StackCfi(_)
+            | Record::StackWin(_) => (),
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Symbols {
+        let origins = mem::take(&mut self.origins);
+        for (line_number, id, rest) in origins {
+            // INLINE_ORIGIN <id> [<file>] <name>
+            // The file identifier is only present along with legacy INLINE
+            // records. It is not needed, since line records cover the file.
+            let name = match rest.find(' ') {
+                Some(index) if self.legacy_inlines => {
+                    if rest[..index].parse::<u32>().is_err() {
+                        let reason = "invalid file identifier";
+                        self.reject(line_number, RecordType::InlineOrigin, reason);
+                        continue;
+                    }
+                    rest[index..].trim_start().to_string()
+                }
+                _ => rest,
+            };
+
+            self.symbols.inline_origins.insert(id, name);
+        }
+
+        let mut symbols = self.symbols;
+        if let Some(ref mut module) = symbols.module {
+            module.code_id = self.code_id;
+            module.code_file = self.code_file;
+        }
+
+        symbols.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        symbols.functions.sort_by_key(|function| function.address);
+        symbols.publics.sort_by_key(|public| public.address);
+        for function in &mut symbols.functions {
+            function.lines.sort_by_key(|line| line.address);
+        }
+
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_module_header() {
+        let symbols = Symbols::parse(
+            b"MODULE windows x86 3249D99D0C4049318610F4E4FB0B69361 crash.pdb\n\
+              INFO CODE_ID 59B0D8F3183000 crash.exe\n",
+        );
+        assert!(symbols.diagnostics.is_empty());
+
+        let module = symbols.module.unwrap();
+        assert_eq!(module.os, "windows");
+        assert_eq!(module.arch, "x86");
+        assert_eq!(module.id.to_string(), "3249D99D0C4049318610F4E4FB0B69361");
+        assert_eq!(module.debug_file, "crash.pdb");
+        assert_eq!(module.code_id, Some("59B0D8F3183000".to_string()));
+        assert_eq!(module.code_file, Some("crash.exe".to_string()));
+    }
+
+    #[test]
+    fn report_rejected_lines() {
+        let symbols = Symbols::parse(
+            b"MODULE mac x86_64 DFB8E43AF2423D73A453AEB6A777EF750 test\n\
+              1000 10 5 0\n\
+              FUNC zzzz 10 0 main\n\
+              FILE 0\n",
+        );
+
+        let diagnostics: Vec<_> = symbols.diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "line 2: invalid line record: line record without a function",
+                "line 3: invalid FUNC record: invalid address",
+                "line 4: invalid FILE record: missing fields",
+            ]
+        );
+    }
+
+    #[test]
+    fn lookup_inlined_address() {
+        let symbols = Symbols::parse(
+            b"FILE 0 main.cpp\n\
+              FILE 1 helpers.h\n\
+              INLINE_ORIGIN 0 outer()\n\
+              INLINE_ORIGIN 1 inner()\n\
+              FUNC 1000 40 0 main\n\
+              INLINE 0 10 0 0 1010 20\n\
+              INLINE 1 20 1 1 1018 8\n\
+              1000 10 5 0\n\
+              1010 8 21 1\n\
+              1018 8 30 1\n\
+              1020 20 11 0\n",
+        );
+        assert!(symbols.diagnostics.is_empty());
+
+        let lookup = symbols.lookup(0x101c).unwrap();
+        assert_eq!(lookup.location.function_name, "main");
+        assert_eq!(lookup.location.file, Some("main.cpp"));
+        assert_eq!(lookup.location.line, Some(10));
+        assert_eq!(lookup.function_address, 0x1000);
+
+        let inlined: Vec<_> = lookup
+            .inlined
+            .iter()
+            .map(|l| (l.function_name, l.file, l.line))
+            .collect();
+        assert_eq!(
+            inlined,
+            vec![
+                ("inner()", Some("helpers.h"), Some(30)),
+                ("outer()", Some("helpers.h"), Some(20)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_legacy_inline_records() {
+        let symbols = Symbols::parse(
+            b"FILE 0 main.cpp\n\
+              INLINE_ORIGIN 0 0 outer()\n\
+              FUNC 1000 40 0 main\n\
+              INLINE 0 10 0 1010 20\n\
+              1000 40 5 0\n",
+        );
+        assert!(symbols.diagnostics.is_empty());
+        assert_eq!(symbols.inline_origins[&0], "outer()");
+
+        let lookup = symbols.lookup(0x1010).unwrap();
+        assert_eq!(lookup.location.line, Some(10));
+        assert_eq!(lookup.inlined[0].function_name, "outer()");
+    }
+
+    #[test]
+    fn lookup_public_fallback() {
+        let symbols = Symbols::parse(
+            b"FUNC 1000 10 0 func\n\
+              PUBLIC 800 0 before\n\
+              PUBLIC 2000 0 after\n",
+        );
+
+        assert_eq!(symbols.lookup(0x1004).unwrap().location.function_name, "func");
+        assert_eq!(symbols.lookup(0x1004).unwrap().source, SymbolSource::Func);
+        assert_eq!(symbols.lookup(0x900).unwrap().location.function_name, "before");
+        assert_eq!(symbols.lookup(0x900).unwrap().source, SymbolSource::Public);
+        assert!(symbols.lookup(0x1800).is_none());
+        assert_eq!(symbols.lookup(0x2004).unwrap().location.function_name, "after");
+    }
+}
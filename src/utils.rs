@@ -1,5 +1,8 @@
+use std::borrow::Cow;
 use std::ffi::CStr;
 use std::fs::File;
+#[cfg(any(feature = "gzip", feature = "zlib", feature = "zstd"))]
+use std::io;
 use std::io::prelude::*;
 use std::os::raw::c_char;
 use std::path::Path;
@@ -32,3 +35,147 @@ pub fn read_buffer<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
     file.read_to_end(&mut buffer)?;
     Ok(buffer)
 }
+
+/// Decompresses a buffer read by `read_buffer`, reusing its allocation if it
+/// is not compressed.
+pub fn decompress_owned(buffer: Vec<u8>) -> Result<Vec<u8>> {
+    let decompressed = match decompress(&buffer)? {
+        Cow::Owned(decompressed) => Some(decompressed),
+        Cow::Borrowed(_) => None,
+    };
+
+    Ok(decompressed.unwrap_or(buffer))
+}
+
+/// The maximum size of decompressed data. Larger outputs are rejected to
+/// guard against decompression bombs.
+#[cfg(any(feature = "gzip", feature = "zlib", feature = "zstd"))]
+const MAX_DECOMPRESSED_SIZE: u64 = 4 << 30;
+
+/// Compression formats recognized by `decompress`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Compression {
+    Gzip,
+    Zlib,
+    Zstd,
+}
+
+/// Detects the compression format of a buffer from its magic number.
+fn detect_compression(buffer: &[u8]) -> Option<Compression> {
+    if buffer.starts_with(&[0x1f, 0x8b]) {
+        return Some(Compression::Gzip);
+    }
+
+    if buffer.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        return Some(Compression::Zstd);
+    }
+
+    // zlib has no magic number, but a header with the compression method,
+    // the window size and a checksum, see RFC 1950. Encoders practically
+    // always use deflate with a 32K window, so the first byte is 0x78.
+    if buffer.len() >= 2 && buffer[0] == 0x78 {
+        let header = u16::from(buffer[0]) << 8 | u16::from(buffer[1]);
+        if header % 31 == 0 {
+            return Some(Compression::Zlib);
+        }
+    }
+
+    None
+}
+
+/// Decompresses gzip, zlib or zstd compressed data. Other data is returned
+/// unchanged.
+///
+/// The format is detected from the magic number of the buffer. Each format
+/// requires its cargo feature, otherwise a `DecompressionError` is returned.
+/// A `DecompressionError` is also returned if the decompressed data exceeds
+/// 4 GiB.
+///
+/// Since zlib headers are only protected by a short checksum, buffers that
+/// look like zlib but are not a valid deflate stream are returned unchanged.
+/// The same applies to all such buffers if the zlib feature is disabled.
+/// Truncated zlib streams are still an error.
+pub fn decompress(buffer: &[u8]) -> Result<Cow<'_, [u8]>> {
+    let decompressed = match detect_compression(buffer) {
+        Some(Compression::Gzip) => decompress_gzip(buffer)?,
+        Some(Compression::Zlib) => match decompress_zlib(buffer)? {
+            Some(decompressed) => decompressed,
+            None => return Ok(Cow::Borrowed(buffer)),
+        },
+        Some(Compression::Zstd) => decompress_zstd(buffer)?,
+        None => return Ok(Cow::Borrowed(buffer)),
+    };
+
+    Ok(Cow::Owned(decompressed))
+}
+
+/// Reads all data from a decompressing reader into `buffer`, up to
+/// `MAX_DECOMPRESSED_SIZE` bytes.
+#[cfg(any(feature = "gzip", feature = "zlib", feature = "zstd"))]
+fn read_decompressed<R: Read>(reader: R, buffer: &mut Vec<u8>) -> io::Result<()> {
+    reader.take(MAX_DECOMPRESSED_SIZE + 1).read_to_end(buffer)?;
+    if buffer.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        let message = "decompressed data exceeds the size limit";
+        return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    }
+
+    Ok(())
+}
+
+/// Converts an error while reading decompressed data.
+#[cfg(any(feature = "gzip", feature = "zlib", feature = "zstd"))]
+fn decompression_error(error: &io::Error) -> ::errors::Error {
+    ::errors::ErrorKind::DecompressionError(error.to_string()).into()
+}
+
+#[cfg(feature = "gzip")]
+fn decompress_gzip(buffer: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    let decoder = ::flate2::read::MultiGzDecoder::new(buffer);
+    read_decompressed(decoder, &mut decompressed).map_err(|e| decompression_error(&e))?;
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress_gzip(_buffer: &[u8]) -> Result<Vec<u8>> {
+    let message = "gzip support requires the gzip feature";
+    Err(::errors::ErrorKind::DecompressionError(message.into()).into())
+}
+
+/// Inflates zlib data. Returns `None` if the buffer does not start with a
+/// valid deflate stream.
+#[cfg(feature = "zlib")]
+fn decompress_zlib(buffer: &[u8]) -> Result<Option<Vec<u8>>> {
+    let mut decompressed = Vec::new();
+    let decoder = ::flate2::read::ZlibDecoder::new(buffer);
+    match read_decompressed(decoder, &mut decompressed) {
+        Ok(()) => Ok(Some(decompressed)),
+        // The stream is corrupt before yielding any data, so the buffer most
+        // likely only resembles a zlib header. Truncated streams end with
+        // `UnexpectedEof` instead and are reported.
+        Err(ref e) if e.kind() == io::ErrorKind::InvalidInput && decompressed.is_empty() => {
+            Ok(None)
+        }
+        Err(e) => Err(decompression_error(&e)),
+    }
+}
+
+/// Without the zlib feature, buffers resembling zlib data are not inflated.
+#[cfg(not(feature = "zlib"))]
+fn decompress_zlib(_buffer: &[u8]) -> Result<Option<Vec<u8>>> {
+    Ok(None)
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(buffer: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    let decoder = ::zstd::stream::read::Decoder::new(buffer)?;
+    read_decompressed(decoder, &mut decompressed).map_err(|e| decompression_error(&e))?;
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_buffer: &[u8]) -> Result<Vec<u8>> {
+    let message = "zstd support requires the zstd feature";
+    Err(::errors::ErrorKind::DecompressionError(message.into()).into())
+}
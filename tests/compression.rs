@@ -0,0 +1,119 @@
+extern crate breakpad;
+extern crate difference;
+#[cfg(feature = "gzip")]
+extern crate flate2;
+
+mod common;
+
+use std::fs::File;
+use std::io::prelude::*;
+
+use breakpad::{decompress, Resolver};
+use common::fixture_path;
+
+fn load_fixture_buffer(file_name: &str) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut file = File::open(fixture_path(file_name)).unwrap();
+    file.read_to_end(&mut buffer).unwrap();
+    buffer
+}
+
+#[cfg(any(feature = "gzip", feature = "zlib", feature = "zstd"))]
+fn assert_resolves(resolver: &Resolver) {
+    assert!(!resolver.corrupt());
+    let info = resolver.lookup(0x19b8).expect("Could not find function");
+    assert_eq!(info.function_name, "(anonymous namespace)::CrashFunction()");
+}
+
+#[test]
+fn pass_uncompressed_data() {
+    let buffer = load_fixture_buffer("crash_macos_func.sym");
+    assert_eq!(&*decompress(&buffer).unwrap(), &buffer[..]);
+    assert_eq!(&*decompress(b"").unwrap(), b"");
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn resolve_gzip_symbols() {
+    let expected = load_fixture_buffer("crash_macos_func.sym");
+    let buffer = load_fixture_buffer("crash_macos_func.sym.gz");
+    assert_eq!(&*decompress(&buffer).unwrap(), &expected[..]);
+
+    assert_resolves(&Resolver::from_buffer(&buffer).unwrap());
+    assert_resolves(&Resolver::from_file(fixture_path("crash_macos_func.sym.gz")).unwrap());
+}
+
+#[test]
+#[cfg(not(feature = "gzip"))]
+fn reject_gzip_without_feature() {
+    let buffer = load_fixture_buffer("crash_macos_func.sym.gz");
+    assert!(decompress(&buffer).is_err());
+    assert!(Resolver::from_buffer(&buffer).is_err());
+}
+
+#[test]
+#[cfg(feature = "zlib")]
+fn resolve_zlib_symbols() {
+    let expected = load_fixture_buffer("crash_macos_func.sym");
+    let buffer = load_fixture_buffer("crash_macos_func.sym.zlib");
+    assert_eq!(&*decompress(&buffer).unwrap(), &expected[..]);
+
+    assert_resolves(&Resolver::from_buffer(&buffer).unwrap());
+}
+
+#[test]
+#[cfg(feature = "zlib")]
+fn reject_truncated_zlib_data() {
+    let buffer = load_fixture_buffer("crash_macos_func.sym.zlib");
+    assert!(decompress(&buffer[..buffer.len() / 2]).is_err());
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn resolve_zstd_symbols() {
+    let expected = load_fixture_buffer("crash_macos_func.sym");
+    let buffer = load_fixture_buffer("crash_macos_func.sym.zst");
+    assert_eq!(&*decompress(&buffer).unwrap(), &expected[..]);
+
+    // Detection does not depend on the file extension.
+    let path = std::env::temp_dir().join("breakpad_compressed_symbols");
+    File::create(&path).unwrap().write_all(&buffer).unwrap();
+    assert_resolves(&Resolver::from_file(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn decompress_files_once() {
+    // A gzip file containing the gzip compressed symbols.
+    let inner = load_fixture_buffer("crash_macos_func.sym.gz");
+    let mut outer = Vec::new();
+    {
+        use flate2::write::GzEncoder;
+        let mut encoder = GzEncoder::new(&mut outer, flate2::Compression::default());
+        encoder.write_all(&inner).unwrap();
+        encoder.finish().unwrap();
+    }
+
+    assert_eq!(&*decompress(&outer).unwrap(), &inner[..]);
+
+    let path = std::env::temp_dir().join("breakpad_double_compressed_symbols");
+    File::create(&path).unwrap().write_all(&outer).unwrap();
+    let resolver = Resolver::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(resolver.module().is_none());
+}
+
+#[test]
+fn pass_data_resembling_zlib() {
+    // Valid header checksum, but not a 32K deflate window.
+    let buffer = [0x08, 0x1d, 0x00];
+    assert_eq!(&*decompress(&buffer).unwrap(), &buffer[..]);
+}
+
+#[test]
+fn pass_invalid_zlib_data() {
+    // "x^" passes the checksum of a zlib header.
+    let buffer = b"x^ is not compressed";
+    assert_eq!(&*decompress(buffer).unwrap(), &buffer[..]);
+}
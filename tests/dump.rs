@@ -0,0 +1,213 @@
+extern crate breakpad;
+extern crate difference;
+
+mod common;
+
+use std::fs::File;
+use std::io::prelude::*;
+
+use breakpad::{CodeModuleId, InfoRecord, Resolver, SymbolFile};
+use common::{assert_snapshot_plain, fixture_path};
+
+fn load_fixture_buffer(file_name: &str) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut file = File::open(fixture_path(file_name)).unwrap();
+    file.read_to_end(&mut buffer).unwrap();
+    buffer
+}
+
+#[test]
+fn dump_elf_symbols() {
+    let buffer = load_fixture_buffer("hello_linux");
+    let symbols = SymbolFile::from_elf(&buffer, "hello_linux").expect("Could not dump symbols");
+
+    let module = symbols.module.as_ref().expect("Missing module record");
+    assert_eq!(module.os, "Linux");
+    assert_eq!(module.arch, "x86_64");
+    assert_eq!(
+        module.id,
+        CodeModuleId::parse("E8E322F36CBC6413E1D889A90F3856B20").unwrap()
+    );
+    assert_eq!(
+        symbols.info,
+        vec![
+            InfoRecord::CodeId {
+                code_id: "F322E3E8BC6C1364E1D889A90F3856B251FF05FD".into(),
+                code_file: None,
+            },
+        ]
+    );
+
+    let mut written = Vec::new();
+    symbols.write(&mut written).expect("Could not write symbols");
+    assert_snapshot_plain("hello_linux.sym", &String::from_utf8(written).unwrap());
+}
+
+#[test]
+fn resolve_dumped_elf_symbols() {
+    let buffer = load_fixture_buffer("hello_linux");
+    let symbols = SymbolFile::from_elf(&buffer, "hello_linux").expect("Could not dump symbols");
+
+    let mut written = Vec::new();
+    symbols.write(&mut written).expect("Could not write symbols");
+    let resolver = Resolver::from_buffer(&written).expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+
+    let info = resolver.lookup(0x84a).expect("Could not find main");
+    assert_eq!(info.function_name, "main");
+    assert_eq!(info.function_address, 0x846);
+    assert_eq!(info.source_file_name.as_ref().unwrap(), "/root/hello.cpp");
+    assert_eq!(info.source_line, Some(6));
+
+    let cfi = symbols
+        .stack_cfi
+        .iter()
+        .find(|record| record.address == 0x846)
+        .expect("Missing CFI for main");
+    assert_eq!(cfi.rules, ".cfa: $rsp 8 + .ra: .cfa -8 + ^");
+    assert_eq!(cfi.deltas.len(), 3);
+}
+
+fn load_dsym_buffer() -> Vec<u8> {
+    load_fixture_buffer("hello_macos.dSYM/Contents/Resources/DWARF/hello_macos")
+}
+
+/// Wraps a thin Mach-O file in a universal binary with a single architecture.
+fn make_fat_binary(buffer: &[u8], cputype: u32) -> Vec<u8> {
+    const OFFSET: u32 = 4096;
+
+    let mut fat = Vec::new();
+    for value in &[0xcafe_babe, 1, cputype, 3, OFFSET, buffer.len() as u32, 12] {
+        fat.extend_from_slice(&[
+            (value >> 24) as u8,
+            (value >> 16) as u8,
+            (value >> 8) as u8,
+            *value as u8,
+        ]);
+    }
+
+    fat.resize(OFFSET as usize, 0);
+    fat.extend_from_slice(buffer);
+    fat
+}
+
+#[test]
+fn dump_macho_symbols() {
+    let buffer = load_fixture_buffer("hello_macos");
+    let dsym = load_dsym_buffer();
+    let symbols = SymbolFile::from_macho_with_dsym(&buffer, &dsym, "hello_macos", None)
+        .expect("Could not dump symbols");
+
+    let module = symbols.module.as_ref().expect("Missing module record");
+    assert_eq!(module.os, "mac");
+    assert_eq!(module.arch, "x86_64");
+    assert_eq!(
+        module.id,
+        CodeModuleId::parse("24CF2A4F38763BCABDEF7542A7A635400").unwrap()
+    );
+
+    let mut written = Vec::new();
+    symbols.write(&mut written).expect("Could not write symbols");
+    assert_snapshot_plain("hello_macos.sym", &String::from_utf8(written).unwrap());
+}
+
+#[test]
+fn resolve_dumped_macho_symbols() {
+    let buffer = load_fixture_buffer("hello_macos");
+    let dsym = load_dsym_buffer();
+    let symbols = SymbolFile::from_macho_with_dsym(&buffer, &dsym, "hello_macos", None)
+        .expect("Could not dump symbols");
+
+    let mut written = Vec::new();
+    symbols.write(&mut written).expect("Could not write symbols");
+    let resolver = Resolver::from_buffer(&written).expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+
+    let info = resolver.lookup(0xf7d).expect("Could not find main");
+    assert_eq!(info.function_name, "main");
+    assert_eq!(info.function_address, 0xf60);
+    assert_eq!(info.source_line, Some(6));
+}
+
+#[test]
+fn dump_macho_without_dsym() {
+    let buffer = load_fixture_buffer("hello_macos");
+    let symbols = SymbolFile::from_macho(&buffer, "hello_macos", None)
+        .expect("Could not dump symbols");
+
+    assert!(symbols.functions.is_empty());
+    assert!(!symbols.publics.is_empty());
+    assert!(!symbols.stack_cfi.is_empty());
+}
+
+#[test]
+fn dump_fat_macho_symbols() {
+    let buffer = make_fat_binary(&load_fixture_buffer("hello_macos"), 0x0100_0007);
+    let dsym = make_fat_binary(&load_dsym_buffer(), 0x0100_0007);
+
+    let symbols = SymbolFile::from_macho_with_dsym(&buffer, &dsym, "hello_macos", Some("x86_64"))
+        .expect("Could not dump symbols");
+    assert_eq!(symbols.module.as_ref().unwrap().arch, "x86_64");
+    assert!(!symbols.functions.is_empty());
+
+    assert!(SymbolFile::from_macho(&buffer, "hello_macos", Some("arm64")).is_err());
+}
+
+#[test]
+fn reject_mismatching_dsym() {
+    let buffer = load_fixture_buffer("hello_macos");
+    let mut dsym = load_dsym_buffer();
+
+    // Corrupt the UUID of the dSYM so that it no longer matches the binary.
+    let uuid = [0x24, 0xcf, 0x2a, 0x4f, 0x38, 0x76, 0x3b, 0xca];
+    let position = dsym
+        .windows(uuid.len())
+        .position(|window| window == uuid)
+        .expect("Missing UUID in dSYM");
+    dsym[position] ^= 0xff;
+
+    assert!(SymbolFile::from_macho_with_dsym(&buffer, &dsym, "hello_macos", None).is_err());
+}
+
+/// Corrupts a mangled name in the string tables of an object file, so that it
+/// ends after `end` bytes with an `E` and contains an invalid UTF-8 sequence.
+/// Once decoded, the replacement character spans the hash position of legacy
+/// Rust symbols.
+fn corrupt_mangled_name(buffer: &mut [u8], name: &[u8], end: usize) {
+    assert_eq!(name[end - 1], b'E');
+
+    let mut found = false;
+    let mut position = 0;
+    while let Some(offset) = buffer[position..]
+        .windows(name.len())
+        .position(|window| window == name)
+    {
+        let start = position + offset;
+        buffer[start + end - 19] = 0xff;
+        buffer[start + end] = 0;
+        position = start + name.len();
+        found = true;
+    }
+
+    assert!(found, "Missing name in fixture");
+}
+
+#[test]
+fn dump_corrupted_elf_names() {
+    let mut buffer = load_fixture_buffer("hello_linux");
+    corrupt_mangled_name(&mut buffer, b"_ZNSt11char_traitsIcE11to_int_typeERKc", 35);
+
+    let symbols = SymbolFile::from_elf(&buffer, "hello_linux").expect("Could not dump symbols");
+    assert!(!symbols.functions.is_empty());
+}
+
+#[test]
+fn dump_corrupted_macho_names() {
+    let buffer = load_fixture_buffer("hello_macos");
+    let mut dsym = load_dsym_buffer();
+    corrupt_mangled_name(&mut dsym, b"_ZNSt3__111char_traitsIcE11eq_int_typeEii", 39);
+
+    let symbols = SymbolFile::from_macho_with_dsym(&buffer, &dsym, "hello_macos", None)
+        .expect("Could not dump symbols");
+    assert!(!symbols.functions.is_empty());
+}
@@ -0,0 +1,73 @@
+extern crate breakpad;
+
+use std::collections::HashMap;
+
+use breakpad::ProgramEvaluator;
+
+fn memory() -> HashMap<u32, u32> {
+    let mut memory = HashMap::new();
+    memory.insert(0x1000, 0x2000);
+    memory.insert(0x1004, 0x0040_1234);
+    memory
+}
+
+#[test]
+fn evaluate_program() {
+    let memory = memory();
+    let mut evaluator = ProgramEvaluator::new(|address| memory.get(&address).cloned());
+    evaluator.set("$ebp", 0x1000);
+    evaluator.set("$esp", 0x0ff0);
+
+    let assigned = evaluator
+        .evaluate("$T0 $ebp = $eip $T0 4 + ^ = $ebp $T0 ^ = $esp $T0 8 + =")
+        .expect("Could not evaluate program");
+
+    assert_eq!(assigned, ["$T0", "$eip", "$ebp", "$esp"]);
+    assert_eq!(evaluator.get("$T0"), Some(0x1000));
+    assert_eq!(evaluator.get("$eip"), Some(0x0040_1234));
+    assert_eq!(evaluator.get("$ebp"), Some(0x2000));
+    assert_eq!(evaluator.get("$esp"), Some(0x1008));
+}
+
+#[test]
+fn evaluate_program_without_spaces() {
+    let memory = memory();
+    let mut evaluator = ProgramEvaluator::new(|address| memory.get(&address).cloned());
+    evaluator.set("$ebp", 0x0f80);
+
+    evaluator
+        .evaluate("$T0 $ebp 128 + =$eip $T0 4 + ^ =$ebp $T0 ^ =")
+        .expect("Could not evaluate program");
+
+    assert_eq!(evaluator.get("$eip"), Some(0x0040_1234));
+    assert_eq!(evaluator.get("$ebp"), Some(0x2000));
+}
+
+#[test]
+fn evaluate_value() {
+    let mut evaluator = ProgramEvaluator::new(|_| None);
+    evaluator.set(".raSearchStart", 0x1007);
+
+    assert_eq!(evaluator.evaluate_value(".raSearchStart 8 @").unwrap(), 0x1000);
+    assert_eq!(evaluator.evaluate_value("0x10 -4 +").unwrap(), 12);
+    assert_eq!(evaluator.evaluate_value("0 4 -").unwrap(), 0xffff_fffc);
+    assert_eq!(evaluator.evaluate_value("7 2 % 9 *").unwrap(), 9);
+}
+
+#[test]
+fn evaluate_invalid_programs() {
+    let mut evaluator = ProgramEvaluator::new(|_| None);
+    evaluator.set("$esp", 0x1000);
+
+    assert!(evaluator.evaluate("$eip $ebp =").is_err());
+    assert!(evaluator.evaluate("$eip $esp ^ =").is_err());
+    assert!(evaluator.evaluate("$eip $esp 0 / =").is_err());
+    assert!(evaluator.evaluate(".raSearch $esp =").is_err());
+    assert!(evaluator.evaluate("$eip $esp = $esp").is_err());
+    assert!(evaluator.evaluate("$eip +").is_err());
+    assert!(evaluator.evaluate_value("$esp $esp").is_err());
+
+    // Assignments before the failing token are kept.
+    assert!(evaluator.evaluate("$T0 $esp = $eip $T0 ^ =").is_err());
+    assert_eq!(evaluator.get("$T0"), Some(0x1000));
+}
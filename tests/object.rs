@@ -0,0 +1,59 @@
+extern crate breakpad;
+extern crate difference;
+
+mod common;
+
+use std::fs::File;
+use std::io::prelude::*;
+
+use breakpad::{object_code_identifier, CodeModuleId};
+use common::fixture_path;
+
+fn load_fixture_buffer(file_name: &str) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut file = File::open(fixture_path(file_name)).unwrap();
+    file.read_to_end(&mut buffer).unwrap();
+    buffer
+}
+
+#[test]
+fn elf_module_id() {
+    let buffer = load_fixture_buffer("hello_linux");
+    assert_eq!(
+        CodeModuleId::from_object_file(&buffer).unwrap(),
+        CodeModuleId::parse("E8E322F36CBC6413E1D889A90F3856B20").unwrap()
+    );
+    assert_eq!(
+        object_code_identifier(&buffer).unwrap(),
+        Some("F322E3E8BC6C1364E1D889A90F3856B251FF05FD".into())
+    );
+}
+
+#[test]
+fn macho_module_id() {
+    let buffer = load_fixture_buffer("hello_macos");
+    assert_eq!(
+        CodeModuleId::from_object_file(&buffer).unwrap(),
+        CodeModuleId::parse("24CF2A4F38763BCABDEF7542A7A635400").unwrap()
+    );
+    assert_eq!(
+        object_code_identifier(&buffer).unwrap(),
+        Some("24CF2A4F38763BCABDEF7542A7A63540".into())
+    );
+}
+
+#[test]
+fn dsym_module_id() {
+    let buffer = load_fixture_buffer("hello_macos.dSYM/Contents/Resources/DWARF/hello_macos");
+    assert_eq!(
+        CodeModuleId::from_object_file(&buffer).unwrap(),
+        CodeModuleId::parse("24CF2A4F38763BCABDEF7542A7A635400").unwrap()
+    );
+}
+
+#[test]
+fn unsupported_object_file() {
+    let buffer = load_fixture_buffer("crash_macos_func.sym");
+    assert!(CodeModuleId::from_object_file(&buffer).is_err());
+    assert!(object_code_identifier(&buffer).is_err());
+}
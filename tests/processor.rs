@@ -7,7 +7,8 @@ use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::prelude::*;
 
-use breakpad::{CodeModuleId, FrameInfoMap, ProcessState};
+use breakpad::{CodeModuleId, CpuFamily, FrameInfoMap, FrameTrust, ProcessState, Processor,
+               SymbolicatedProcess, UnwindMethod, WalkTermination};
 use common::{assert_snapshot, fixture_path, load_fixture};
 
 #[test]
@@ -31,6 +32,15 @@ fn process_minidump_from_buffer() {
     assert_snapshot("process_state.txt", &state);
 }
 
+#[test]
+#[cfg(feature = "gzip")]
+fn process_compressed_minidump() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp.gz"), None)
+        .expect("Could not process minidump");
+
+    assert_snapshot("process_state.txt", &state);
+}
+
 #[test]
 fn obtain_referenced_modules() {
     let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None)
@@ -40,6 +50,32 @@ fn obtain_referenced_modules() {
     assert_snapshot("referenced_modules.txt", &modules);
 }
 
+#[test]
+fn distinguish_modules_without_identifier() {
+    let mut buffer = Vec::new();
+    let mut file = File::open(fixture_path("crash_macos.dmp")).expect("Could not open minidump");
+    file.read_to_end(&mut buffer)
+        .expect("Could not read minidump");
+
+    let state = ProcessState::from_minidump_buffer(buffer.as_slice(), None)
+        .expect("Could not process minidump");
+    let module_count = state.referenced_modules().len();
+    assert!(module_count > 1);
+
+    // Invalidate the CodeView records, which carry the debug identifiers.
+    for index in 0..buffer.len() - 3 {
+        if &buffer[index..index + 4] == b"RSDS" {
+            buffer[index..index + 4].copy_from_slice(b"XXXX");
+        }
+    }
+
+    let state = ProcessState::from_minidump_buffer(buffer.as_slice(), None)
+        .expect("Could not process minidump");
+    let modules = state.referenced_modules();
+    assert!(modules.iter().all(|module| module.try_id().is_none()));
+    assert_eq!(modules.len(), module_count);
+}
+
 #[test]
 fn get_minidump_process_state_cfi() {
     let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
@@ -54,3 +90,140 @@ fn get_minidump_process_state_cfi() {
 
     assert_snapshot("process_state_cfi.txt", &state);
 }
+
+#[test]
+fn get_return_and_lookup_addresses() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None)
+        .expect("Could not process minidump");
+
+    let frames = state.threads()[0].frames();
+    assert_eq!(frames[0].cpu_family(), CpuFamily::AMD64);
+    assert_eq!(frames[0].return_address(), frames[0].instruction());
+    assert_eq!(frames[0].instruction_adjustment(), 0);
+    assert_eq!(frames[0].lookup_address(), frames[0].instruction());
+
+    for frame in &frames[1..] {
+        assert_eq!(frame.return_address(), frame.instruction() + 1);
+        assert_eq!(frame.instruction_adjustment(), 1);
+        assert_eq!(frame.lookup_address(), frame.instruction());
+    }
+}
+
+#[test]
+fn rewalk_with_additional_cfi() {
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let module_cfi = load_fixture("crash_macos_cfi.sym").expect("Could not load CFI symbols");
+
+    let mut symbols = FrameInfoMap::new();
+    symbols.insert(module_id, module_cfi.as_bytes());
+
+    let mut state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None)
+        .expect("Could not process minidump");
+
+    let rewalked = state.rewalk(&symbols).expect("Could not rewalk threads");
+    assert_eq!(rewalked, vec![0]);
+    assert_snapshot("process_state_cfi.txt", &state);
+}
+
+#[test]
+fn reuse_cached_cfi_in_processor() {
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let module_cfi = load_fixture("crash_macos_cfi.sym").expect("Could not load CFI symbols");
+
+    let mut symbols = FrameInfoMap::new();
+    symbols.insert(module_id, module_cfi.as_bytes());
+
+    let mut processor = Processor::new();
+    let state = processor
+        .process_minidump_file(fixture_path("crash_macos.dmp"), Some(&symbols))
+        .expect("Could not process minidump");
+    assert_snapshot("process_state_cfi.txt", &state);
+
+    let state = processor
+        .process_minidump_file(fixture_path("crash_macos.dmp"), None)
+        .expect("Could not process minidump");
+    assert_snapshot("process_state_cfi.txt", &state);
+
+    assert!(processor.evict(&module_id));
+    assert!(!processor.evict(&module_id));
+
+    let state = processor
+        .process_minidump_file(fixture_path("crash_macos.dmp"), None)
+        .expect("Could not process minidump");
+    assert_snapshot("process_state.txt", &state);
+}
+
+#[test]
+fn symbolicate_while_stackwalking() {
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let module_symbols = load_fixture("crash_macos_func.sym").expect("Could not load symbols");
+
+    let mut symbols = FrameInfoMap::new();
+    symbols.insert(module_id, module_symbols.as_bytes());
+
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), Some(&symbols))
+        .expect("Could not process minidump");
+
+    let frame = state.threads()[0].frames()[0];
+    assert_eq!(frame.function_name(), "(anonymous namespace)::CrashFunction()");
+    assert_eq!(
+        frame.source_file_name(),
+        "/Users/jauer/Coding/breakpad/examples/../examples/crash_macos.cpp"
+    );
+    assert_eq!(frame.source_line(), 11);
+}
+
+#[test]
+fn symbolicate_minidump_with_symbols() {
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let module_symbols = load_fixture("crash_macos_func.sym").expect("Could not load symbols");
+
+    let mut symbols = FrameInfoMap::new();
+    symbols.insert(module_id, module_symbols.as_bytes());
+
+    let process =
+        SymbolicatedProcess::from_minidump_file(fixture_path("crash_macos.dmp"), &symbols)
+            .expect("Could not symbolicate minidump");
+
+    let frame = &process.threads[0].frames[0];
+    let symbol = frame.symbol.as_ref().expect("Could not find symbol");
+    assert_eq!(symbol.function_name, "(anonymous namespace)::CrashFunction()");
+    assert_eq!(symbol.source_line, Some(11));
+}
+
+#[test]
+fn record_stackwalk_trace() {
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let module_cfi = load_fixture("crash_macos_cfi.sym").expect("Could not load CFI symbols");
+
+    let mut symbols = FrameInfoMap::new();
+    symbols.insert(module_id, module_cfi.as_bytes());
+
+    let mut processor = Processor::new();
+    let state = processor
+        .process_minidump_file(fixture_path("crash_macos.dmp"), Some(&symbols))
+        .expect("Could not process minidump");
+    assert!(state.stackwalk_trace(0).is_none());
+
+    processor.set_stackwalk_trace(true);
+    let state = processor
+        .process_minidump_file(fixture_path("crash_macos.dmp"), Some(&symbols))
+        .expect("Could not process minidump");
+
+    let stack = state.threads()[0];
+    let trace = state.stackwalk_trace(0).expect("Could not get trace");
+    assert_eq!(trace.frames.len(), stack.frames().len());
+    assert_eq!(trace.frames[0].trust, FrameTrust::Context);
+    assert_eq!(trace.frames[0].unwind_method, Some(UnwindMethod::Cfi));
+    assert!(trace.frames[0].cfi_searched);
+    assert!(trace.frames[0].cfi_rules.is_some());
+    assert_eq!(trace.frames[1].trust, FrameTrust::CFI);
+    assert_eq!(trace.frames.last().unwrap().unwind_method, None);
+    assert_eq!(trace.termination, WalkTermination::NoCaller);
+    assert!(state.stackwalk_trace(1).is_none());
+}
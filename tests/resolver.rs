@@ -6,7 +6,8 @@ mod common;
 use std::fs::File;
 use std::io::prelude::*;
 
-use breakpad::{ProcessState, Resolver};
+use breakpad::{CodeModuleId, DemangleOptions, ProcessState, RecordType, Resolver, SymbolCache,
+               SymbolSource, SymbolicatedProcess};
 use common::{assert_snapshot, fixture_path};
 
 #[test]
@@ -35,6 +36,43 @@ fn create_corrupt_resolver() {
     assert!(resolver.corrupt());
 }
 
+#[test]
+fn report_corrupt_lines() {
+    let resolver =
+        Resolver::from_file(fixture_path("Corrupt.sym")).expect("Could not load symbols");
+
+    let diagnostics = resolver.diagnostics();
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[0].line, 2);
+    assert_eq!(diagnostics[0].record_type, RecordType::Line);
+    assert_eq!(diagnostics[0].reason, "line record without a function");
+}
+
+#[test]
+fn reject_corrupt_symbols_in_strict_mode() {
+    let error = match Resolver::from_file_strict(fixture_path("Corrupt.sym")) {
+        Ok(_) => panic!("Loaded corrupt symbols"),
+        Err(error) => error,
+    };
+    assert_eq!(
+        error.to_string(),
+        "Resolver Error: line 2: invalid line record: line record without a function"
+    );
+
+    Resolver::from_file_strict(fixture_path("crash_macos_func.sym"))
+        .expect("Could not load symbols");
+}
+
+#[test]
+fn reject_non_ascii_module_id() {
+    let buffer = b"MODULE mac x86_64 DFB8E43AF2423D73A453AEB6A777EF7\xc3\xa90 foo\n";
+
+    let resolver = Resolver::from_buffer(buffer).expect("Could not load symbols");
+    assert!(resolver.corrupt());
+    assert!(resolver.module().is_none());
+    assert!(Resolver::from_buffer_strict(buffer).is_err());
+}
+
 #[test]
 fn resolve_stack_frame() {
     let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None).unwrap();
@@ -47,3 +85,224 @@ fn resolve_stack_frame() {
     let resolved_frame = resolver.resolve_frame(&frame);
     assert_snapshot("resolved_frame.txt", &resolved_frame);
 }
+
+#[test]
+fn read_module_header() {
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+
+    let module = resolver.module().expect("Missing module record");
+    assert_eq!(module.os, "mac");
+    assert_eq!(module.arch, "x86_64");
+    assert_eq!(module.id.to_string(), "DFB8E43AF2423D73A453AEB6A777EF750");
+    assert_eq!(module.debug_file, "crash_macos");
+    assert_eq!(module.code_id, None);
+}
+
+#[test]
+fn resolve_frame_with_matching_module() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None).unwrap();
+    let frame = state.threads()[0].frames()[0];
+
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+    let resolved_frame = resolver
+        .resolve_frame_checked(frame)
+        .expect("Could not resolve frame");
+    assert_snapshot("resolved_frame.txt", &resolved_frame);
+
+    let resolver =
+        Resolver::from_file(fixture_path("Corrupt.sym")).expect("Could not load symbols");
+    assert!(resolver.resolve_frame_checked(frame).is_err());
+}
+
+#[test]
+fn resolve_function_metadata() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None).unwrap();
+    let frame = state.threads()[0].frames()[0];
+    let base_address = frame.module().unwrap().base_address();
+
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+
+    let resolved_frame = resolver.resolve_frame(frame);
+    assert_eq!(resolved_frame.function_base(), base_address + 0x19b0);
+    assert_eq!(resolved_frame.function_size(), 0x18);
+    assert_eq!(resolved_frame.function_offset(), 0x11);
+    assert_eq!(resolved_frame.source_line_base(), base_address + 0x19c1);
+    assert_eq!(resolved_frame.parameter_size(), 0);
+    assert_eq!(resolved_frame.symbol_source(), SymbolSource::Func);
+    assert_eq!(resolved_frame.record_address(), Some(0x19b0));
+
+    let unresolved_frame = SymbolCache::new().resolve_frame(frame);
+    assert_eq!(unresolved_frame.symbol_source(), SymbolSource::None);
+    assert_eq!(unresolved_frame.record_address(), None);
+    assert_eq!(unresolved_frame.function_base(), 0);
+    assert_eq!(unresolved_frame.function_offset(), 0);
+}
+
+#[test]
+fn lookup_relative_address() {
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+
+    let info = resolver.lookup(0x19c1).expect("Could not find symbol");
+    assert_eq!(info.function_name, "(anonymous namespace)::CrashFunction()");
+    assert_eq!(info.function_address, 0x19b0);
+    assert_eq!(info.symbol_source, SymbolSource::Func);
+    assert_eq!(
+        info.source_file_name.as_deref(),
+        Some("/Users/jauer/Coding/breakpad/examples/../examples/crash_macos.cpp")
+    );
+    assert_eq!(info.source_line, Some(11));
+
+    assert_eq!(resolver.lookup(0x10), None);
+}
+
+#[test]
+fn resolve_inlined_frames() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None).unwrap();
+    let frame = state.threads()[0].frames()[0];
+
+    let resolver = Resolver::from_file(fixture_path("crash_macos_inline.sym"))
+        .expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+
+    let resolved_frame = resolver.resolve_frame(frame);
+    assert_snapshot("resolved_frame.txt", &resolved_frame);
+
+    let helpers = "/Users/jauer/Coding/breakpad/examples/../examples/crash_helpers.h";
+    let inlined: Vec<_> = resolved_frame
+        .inlined_frames()
+        .iter()
+        .map(|frame| {
+            (
+                frame.function_name.as_str(),
+                frame.source_file_name.as_deref(),
+                frame.source_line,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        inlined,
+        vec![
+            ("(anonymous namespace)::InnerHelper()", Some(helpers), Some(8)),
+            ("(anonymous namespace)::Helper()", Some(helpers), Some(4)),
+        ]
+    );
+}
+
+#[test]
+fn demangle_resolved_names() {
+    let mut resolver = Resolver::from_buffer(
+        b"PUBLIC 1000 0 _ZN4core9panicking5panic17h5a0b3c4d5e6f7a8bE\n\
+          FUNC 2000 10 0 _ZN3foo3barEi\n",
+    ).expect("Could not load symbols");
+
+    assert_eq!(
+        resolver.lookup(0x1004).unwrap().function_name,
+        "_ZN4core9panicking5panic17h5a0b3c4d5e6f7a8bE"
+    );
+
+    resolver.set_demangle_options(Some(DemangleOptions {
+        arguments: false,
+        rust_hash: false,
+    }));
+    assert_eq!(
+        resolver.lookup(0x1004).unwrap().function_name,
+        "core::panicking::panic"
+    );
+    assert_eq!(resolver.lookup(0x2004).unwrap().function_name, "foo::bar");
+}
+
+#[test]
+fn iterate_symbol_records() {
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+
+    let files: Vec<_> = resolver.files().collect();
+    assert_eq!(
+        files,
+        vec![
+            (
+                14,
+                "/Users/jauer/Coding/breakpad/examples/../examples/crash_macos.cpp",
+            ),
+        ]
+    );
+
+    let functions: Vec<_> = resolver.functions().collect();
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].address(), 0x19b0);
+    assert_eq!(functions[0].size(), 0x18);
+    assert_eq!(functions[0].name(), "(anonymous namespace)::CrashFunction()");
+
+    let lines: Vec<_> = functions[0]
+        .lines()
+        .map(|line| (line.address(), line.size(), line.line(), line.file_id()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (0x19b0, 7, 9, 14),
+            (0x19b7, 5, 10, 14),
+            (0x19bc, 5, 11, 14),
+            (0x19c1, 6, 11, 14),
+            (0x19c7, 1, 12, 14),
+        ]
+    );
+
+    assert_eq!(resolver.public_symbols().count(), 0);
+}
+
+#[test]
+fn resolve_process_state_with_cache() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None).unwrap();
+
+    let module_id = CodeModuleId::parse("DFB8E43AF2423D73A453AEB6A777EF750")
+        .expect("Could not parse CodeModule ID");
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+
+    let mut cache = SymbolCache::new();
+    cache.insert(module_id, resolver);
+
+    let threads = cache.resolve_process_state(&state);
+    assert_eq!(threads.len(), state.threads().len());
+    assert_eq!(threads[0].len(), state.threads()[0].frames().len());
+    assert_snapshot("resolved_frame.txt", &threads[0][0]);
+
+    cache.remove(&module_id);
+    let frame = cache.resolve_frame(state.threads()[0].frames()[0]);
+    assert_eq!(frame.function_name(), "");
+}
+
+#[test]
+fn symbolicate_process_state() {
+    let state = ProcessState::from_minidump_file(fixture_path("crash_macos.dmp"), None).unwrap();
+    let resolver =
+        Resolver::from_file(fixture_path("crash_macos_func.sym")).expect("Could not load symbols");
+
+    let mut cache = SymbolCache::new();
+    cache.insert(resolver.module().unwrap().id, resolver);
+
+    let process = SymbolicatedProcess::from_process_state(&state, &cache);
+    drop(state);
+
+    assert_eq!(process.threads.len(), 1);
+    assert_eq!(process.threads[0].thread_id, 775);
+
+    let frame = &process.threads[0].frames[0];
+    assert_eq!(frame.instruction, 4554197441);
+
+    let module = frame.module.as_ref().expect("Missing module");
+    let id = module.id.expect("Missing module id");
+    assert_eq!(id.to_string(), "DFB8E43AF2423D73A453AEB6A777EF750");
+    assert_eq!(module.base_address, 4554190848);
+    assert!(process.modules.contains(module));
+
+    let symbol = frame.symbol.as_ref().expect("Missing symbol");
+    assert_eq!(symbol.function_name, "(anonymous namespace)::CrashFunction()");
+    assert_eq!(symbol.source_line, Some(11));
+}
@@ -0,0 +1,464 @@
+extern crate breakpad;
+extern crate difference;
+
+mod common;
+
+use std::fs::File;
+use std::io::prelude::*;
+
+use breakpad::{FuncRecord, InfoRecord, LineRecord, MergeConflict, MergeOptions, Precedence,
+               ProgramEvaluator, Record, RecordType, Records, Resolver, SplitSymbolFile,
+               StackWinType, SymbolFile};
+use common::fixture_path;
+
+fn assert_round_trip(file_name: &str) {
+    let mut buffer = Vec::new();
+    let mut file = File::open(fixture_path(file_name)).unwrap();
+    file.read_to_end(&mut buffer).unwrap();
+
+    let symbol_file = SymbolFile::parse(&buffer).expect("Could not parse symbols");
+    let mut written = Vec::new();
+    symbol_file.write(&mut written).expect("Could not write symbols");
+    assert_eq!(String::from_utf8(written.clone()).unwrap(), String::from_utf8(buffer).unwrap());
+
+    let resolver = Resolver::from_buffer(&written).expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+}
+
+#[test]
+fn parse_function_symbols() {
+    let file = SymbolFile::from_file(fixture_path("crash_macos_func.sym"))
+        .expect("Could not parse symbols");
+
+    let module = file.module.expect("Missing module record");
+    assert_eq!(module.os, "mac");
+    assert_eq!(module.arch, "x86_64");
+    assert_eq!(module.id.to_string(), "DFB8E43AF2423D73A453AEB6A777EF750");
+    assert_eq!(module.name, "crash_macos");
+
+    assert_eq!(file.files.len(), 1);
+    assert_eq!(file.files[0].id, 14);
+
+    assert_eq!(file.functions.len(), 1);
+    let function = &file.functions[0];
+    assert_eq!(function.address, 0x19b0);
+    assert_eq!(function.size, 0x18);
+    assert_eq!(function.name, "(anonymous namespace)::CrashFunction()");
+    assert_eq!(function.lines.len(), 5);
+    assert_eq!(
+        function.lines[0],
+        LineRecord {
+            address: 0x19b0,
+            size: 7,
+            line: 9,
+            file_id: 14,
+        }
+    );
+}
+
+#[test]
+fn parse_cfi_symbols() {
+    let file = SymbolFile::from_file(fixture_path("crash_macos_cfi.sym"))
+        .expect("Could not parse symbols");
+
+    assert_eq!(file.files.len(), 47);
+    assert!(file.functions.is_empty());
+    assert_eq!(file.stack_cfi.len(), 173);
+
+    let deltas: usize = file.stack_cfi.iter().map(|cfi| cfi.deltas.len()).sum();
+    assert_eq!(file.stack_cfi.len() + deltas, 240);
+}
+
+#[test]
+fn stream_records() {
+    let buffer = b"MODULE windows x86 3249D99D0C4049318610F4E4FB0B69361 crash.pdb\n\
+                   INFO CODE_ID 59B0D8F3183000 crash.exe\n\
+                   FUNC m 1000 10 4 main\n\
+                   STACK WIN 4 1000 10 3 0 4 0 8 0 1 $T0 .raSearch = $eip $T0 ^ =\n\
+                   bogus\n";
+
+    let records: Vec<_> = Records::new(buffer).collect();
+    assert_eq!(records.len(), 5);
+
+    match records[1] {
+        Ok(Record::Info(InfoRecord::CodeId { ref code_id, ref code_file })) => {
+            assert_eq!(code_id, "59B0D8F3183000");
+            assert_eq!(code_file.as_ref().map(String::as_str), Some("crash.exe"));
+        }
+        ref other => panic!("Unexpected record {:?}", other),
+    }
+
+    match records[2] {
+        Ok(Record::Func(FuncRecord {
+            multiple,
+            parameter_size,
+            ..
+        })) => {
+            assert!(multiple);
+            assert_eq!(parameter_size, 4);
+        }
+        ref other => panic!("Unexpected record {:?}", other),
+    }
+
+    match records[3] {
+        Ok(Record::StackWin(ref win)) => {
+            assert_eq!(win.frame_type, StackWinType::FrameData);
+            assert_eq!(win.size, 0x10);
+            assert_eq!(win.local_size, 8);
+            assert_eq!(
+                win.program_string.as_deref(),
+                Some("$T0 .raSearch = $eip $T0 ^ =")
+            );
+        }
+        ref other => panic!("Unexpected record {:?}", other),
+    }
+
+    let diagnostic = records[4].as_ref().unwrap_err();
+    assert_eq!(diagnostic.line, 5);
+    assert_eq!(diagnostic.record_type, RecordType::Line);
+}
+
+#[test]
+fn reject_corrupt_symbol_file() {
+    let error = match SymbolFile::from_file(fixture_path("Corrupt.sym")) {
+        Ok(_) => panic!("Parsed corrupt symbols"),
+        Err(error) => error,
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "Symbol File Error: line 2: invalid line record: line record without a function"
+    );
+}
+
+#[test]
+fn write_function_symbols() {
+    assert_round_trip("crash_macos_func.sym");
+}
+
+#[test]
+fn write_cfi_symbols() {
+    assert_round_trip("crash_macos_cfi.sym");
+}
+
+#[test]
+fn write_streamed_records() {
+    let buffer = "MODULE windows x86 3249D99D0C4049318610F4E4FB0B69361 crash.pdb\n\
+                  INFO CODE_ID 59B0D8F3183000 crash.exe\n\
+                  INLINE_ORIGIN 0 inlined()\n\
+                  FUNC m 1000 10 4 main\n\
+                  INLINE 0 5 1 0 1004 8\n\
+                  1000 10 5 1\n\
+                  PUBLIC m 2000 0 exported\n\
+                  STACK WIN 4 1000 10 3 0 4 0 8 0 1 $T0 .raSearch = $eip $T0 ^ =\n\
+                  STACK WIN 0 2000 10 0 0 0 0 0 0 0 1\n";
+
+    let written: String = Records::new(buffer.as_bytes())
+        .map(|record| format!("{}\n", record.expect("Could not parse record")))
+        .collect();
+
+    assert_eq!(written, buffer);
+}
+
+#[test]
+fn reject_non_ascii_module_id() {
+    let buffer = b"MODULE mac x86_64 DFB8E43AF2423D73A453AEB6A777EF7\xc3\xa90 foo\n";
+
+    let records: Vec<_> = Records::new(buffer).collect();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].is_err());
+    assert!(SymbolFile::parse(buffer).is_err());
+}
+
+#[test]
+fn reject_invalid_cfi_rules() {
+    let buffer = b"STACK CFI INIT 1000 10 .cfa: $rsp 8 + .ra: .cfa -8 + ^\n\
+                   STACK CFI 1004 .cfa: $rsp 16 + $rbp: .cfa -16 + ^\n\
+                   STACK CFI INIT 2000 10 .ra: .cfa -8 + ^\n\
+                   STACK CFI 2004 .cfa: $rsp +\n\
+                   STACK CFI 2008 $rsp 16 +\n\
+                   STACK CFI 200c .cfa:\n";
+
+    let reasons: Vec<_> = Records::new(buffer)
+        .map(|record| record.err().map(|diagnostic| diagnostic.reason))
+        .collect();
+
+    assert_eq!(
+        reasons,
+        vec![
+            None,
+            None,
+            Some("missing .cfa rule".to_string()),
+            Some("invalid CFI rule expression".to_string()),
+            Some("missing CFI rule register".to_string()),
+            Some("invalid CFI rule expression".to_string()),
+        ]
+    );
+
+    let resolver = Resolver::from_buffer(buffer).expect("Could not load symbols");
+    assert_eq!(resolver.diagnostics().len(), 4);
+    assert!(Resolver::from_buffer_strict(buffer).is_err());
+}
+
+#[test]
+fn find_stack_win_records() {
+    let file = SymbolFile::parse(
+        b"MODULE windows x86 3249D99D0C4049318610F4E4FB0B69361 crash.pdb\n\
+          STACK WIN 0 1000 20 3 1 4 8 10 0 0 1\n\
+          STACK WIN 4 1000 10 3 1 4 8 10 0 1 $T0 .raSearch = $eip $T0 ^ = $esp $T0 4 + =\n\
+          STACK WIN 3 2000 10 0 0 0 0 0 0 0 1\n",
+    ).expect("Could not parse symbols");
+
+    let win = file.find_stack_win(0x100f).expect("Missing STACK WIN record");
+    assert_eq!(win.frame_type, StackWinType::FrameData);
+    assert_eq!(win.prologue_size, 3);
+    assert_eq!(win.epilogue_size, 1);
+    assert_eq!(win.parameter_size, 4);
+    assert_eq!(win.saved_register_size, 8);
+    assert_eq!(win.local_size, 0x10);
+
+    let mut evaluator = ProgramEvaluator::new(|address| match address {
+        0x3000 => Some(0x0040_1000),
+        _ => None,
+    });
+    evaluator.set(".raSearch", 0x3000);
+    let program = win.program_string.as_ref().expect("Missing program string");
+    evaluator.evaluate(program).expect("Could not evaluate program");
+    assert_eq!(evaluator.get("$eip"), Some(0x0040_1000));
+    assert_eq!(evaluator.get("$esp"), Some(0x3004));
+
+    let win = file.find_stack_win(0x1010).expect("Missing STACK WIN record");
+    assert_eq!(win.frame_type, StackWinType::Fpo);
+    assert!(win.allocates_base_pointer);
+
+    let win = file.find_stack_win(0x2000).expect("Missing STACK WIN record");
+    assert_eq!(win.frame_type, StackWinType::Standard);
+
+    assert!(file.find_stack_win(0x0fff).is_none());
+    assert!(file.find_stack_win(0x2010).is_none());
+}
+
+#[test]
+fn split_symbol_file() {
+    let mut binary = Vec::new();
+    let mut file = File::open(fixture_path("hello_linux")).unwrap();
+    file.read_to_end(&mut binary).unwrap();
+
+    let full = SymbolFile::from_elf(&binary, "hello_linux").expect("Could not dump symbols");
+    let mut buffer = Vec::new();
+    full.write(&mut buffer).expect("Could not write symbols");
+
+    let split = SplitSymbolFile::split(&buffer);
+    let frame_info = SymbolFile::parse(&split.frame_info).expect("Could not parse frame info");
+    let symbols = SymbolFile::parse(&split.symbols).expect("Could not parse symbols");
+
+    assert_eq!(frame_info.module, full.module);
+    assert_eq!(frame_info.stack_cfi, full.stack_cfi);
+    assert!(frame_info.functions.is_empty());
+    assert!(frame_info.files.is_empty());
+    assert!(frame_info.publics.is_empty());
+
+    assert_eq!(symbols.module, full.module);
+    assert_eq!(symbols.functions, full.functions);
+    assert_eq!(symbols.publics, full.publics);
+    assert_eq!(symbols.files, full.files);
+    assert_eq!(symbols.info, full.info);
+    assert!(symbols.stack_cfi.is_empty());
+
+    let resolver = Resolver::from_buffer(&split.symbols).expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+}
+
+#[test]
+fn merge_split_symbol_files() {
+    let mut binary = Vec::new();
+    let mut file = File::open(fixture_path("hello_linux")).unwrap();
+    file.read_to_end(&mut binary).unwrap();
+
+    let full = SymbolFile::from_elf(&binary, "hello_linux").expect("Could not dump symbols");
+    let mut buffer = Vec::new();
+    full.write(&mut buffer).expect("Could not write symbols");
+
+    let split = SplitSymbolFile::split(&buffer);
+    let files = [
+        SymbolFile::parse(&split.frame_info).expect("Could not parse frame info"),
+        SymbolFile::parse(&split.symbols).expect("Could not parse symbols"),
+    ];
+
+    let merged = SymbolFile::merge(&files, &MergeOptions::default()).expect("Could not merge");
+    assert!(merged.conflicts.is_empty());
+    assert_eq!(merged.file.module, full.module);
+    assert_eq!(merged.file.info, full.info);
+    assert_eq!(merged.file.publics, full.publics);
+    assert_eq!(merged.file.stack_cfi, full.stack_cfi);
+    assert_eq!(merged.file.functions.len(), full.functions.len());
+
+    let mut written = Vec::new();
+    merged.file.write(&mut written).expect("Could not write symbols");
+    let resolver = Resolver::from_buffer(&written).expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+}
+
+#[test]
+fn merge_conflicting_symbols() {
+    let first = SymbolFile::parse(
+        b"MODULE Linux x86_64 0D4A5B2F5E2B4DA3A0E5D1A2B3C4D5E60 test\n\
+          FILE 0 a.c\n\
+          FUNC 1000 10 0 a\n\
+          1000 10 1 0\n\
+          FUNC 1010 10 0 b\n\
+          1010 10 2 0\n\
+          PUBLIC 1000 0 a\n",
+    ).unwrap();
+    let second = SymbolFile::parse(
+        b"MODULE Linux x86_64 0D4A5B2F5E2B4DA3A0E5D1A2B3C4D5E60 test\n\
+          FILE 0 c.c\n\
+          FILE 1 b.c\n\
+          FUNC 1010 10 0 b\n\
+          1010 10 3 1\n\
+          FUNC 1018 10 0 c\n\
+          1018 10 4 0\n\
+          FUNC 2000 10 0 d\n\
+          2000 10 5 0\n\
+          PUBLIC 1000 0 other\n",
+    ).unwrap();
+    let files = [first, second];
+
+    let merged = SymbolFile::merge(&files, &MergeOptions::default()).expect("Could not merge");
+    let names: Vec<_> = merged.file.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["a", "b", "d"]);
+    assert_eq!(merged.file.files.len(), 2);
+    assert_eq!(merged.file.files[1].name, "c.c");
+    assert_eq!(merged.file.functions[2].lines[0].file_id, 1);
+    assert_eq!(merged.file.publics.len(), 1);
+    assert_eq!(merged.file.publics[0].name, "a");
+    assert_eq!(
+        merged.conflicts,
+        [
+            MergeConflict {
+                record_type: RecordType::Public,
+                address: 0x1000,
+                kept: 0,
+                dropped: 1,
+            },
+            MergeConflict {
+                record_type: RecordType::Func,
+                address: 0x1018,
+                kept: 0,
+                dropped: 1,
+            },
+        ]
+    );
+
+    let options = MergeOptions {
+        functions: Precedence::Last,
+        ..MergeOptions::default()
+    };
+    let merged = SymbolFile::merge(&files, &options).expect("Could not merge");
+    let names: Vec<_> = merged.file.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["a", "b", "c", "d"]);
+    assert_eq!(merged.file.functions[1].lines[0].line, 3);
+    assert_eq!(merged.conflicts.len(), 1);
+    assert_eq!(merged.conflicts[0].record_type, RecordType::Public);
+}
+
+#[test]
+fn merge_nested_ranges() {
+    let first = SymbolFile::parse(
+        b"MODULE Linux x86_64 0D4A5B2F5E2B4DA3A0E5D1A2B3C4D5E60 test\n\
+          FUNC 0 1000 0 outer\n\
+          FUNC 10 10 0 inner\n",
+    ).unwrap();
+    let second = SymbolFile::parse(
+        b"MODULE Linux x86_64 0D4A5B2F5E2B4DA3A0E5D1A2B3C4D5E60 test\n\
+          FUNC 10 10 0 inner\n\
+          FUNC 500 10 0 other\n\
+          FUNC 1000 10 0 after\n",
+    ).unwrap();
+
+    let merged =
+        SymbolFile::merge(&[first, second], &MergeOptions::default()).expect("Could not merge");
+    let names: Vec<_> = merged.file.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["outer", "inner", "after"]);
+    assert_eq!(
+        merged.conflicts,
+        [
+            MergeConflict {
+                record_type: RecordType::Func,
+                address: 0x500,
+                kept: 0,
+                dropped: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn merge_dangling_references() {
+    let first = SymbolFile::parse(
+        b"FILE 0 a.c\n\
+          FUNC 1000 10 0 a\n\
+          1000 10 1 5\n",
+    ).unwrap();
+    let second = SymbolFile::parse(
+        b"FILE 0 b.c\n\
+          FUNC 2000 10 0 b\n\
+          2000 10 1 0\n",
+    ).unwrap();
+
+    let merged = SymbolFile::merge(&[first, second], &MergeOptions::default()).unwrap();
+    let file_name = |id| {
+        let file = merged.file.files.iter().find(|file| file.id == id).unwrap();
+        file.name.as_str()
+    };
+
+    assert_eq!(file_name(merged.file.functions[0].lines[0].file_id), "<unknown>");
+    assert_eq!(file_name(merged.file.functions[1].lines[0].file_id), "b.c");
+}
+
+#[test]
+fn merge_legacy_inline_origins() {
+    let first = SymbolFile::parse(
+        b"FILE 0 a.c\n\
+          FILE 1 inlined.h\n\
+          INLINE_ORIGIN 0 1 inlined\n\
+          FUNC 1000 10 0 a\n\
+          INLINE 0 5 0 1000 8\n\
+          1000 10 1 0\n",
+    ).unwrap();
+    let second = SymbolFile::parse(
+        b"FILE 0 b.c\n\
+          FILE 1 other.h\n\
+          FILE 2 inlined.h\n\
+          INLINE_ORIGIN 3 2 inlined\n\
+          FUNC 2000 10 0 b\n\
+          INLINE 0 7 3 2000 8\n\
+          2000 10 1 0\n",
+    ).unwrap();
+
+    let merged = SymbolFile::merge(&[first, second], &MergeOptions::default()).unwrap();
+    assert_eq!(merged.file.inline_origins.len(), 1);
+    let file_id = merged.file.files.iter().find(|file| file.name == "inlined.h").unwrap().id;
+    assert_eq!(merged.file.inline_origins[0].name, format!("{} inlined", file_id));
+    assert_eq!(merged.file.functions[1].inlines[0].origin_id, merged.file.inline_origins[0].id);
+
+    let mut buffer = Vec::new();
+    merged.file.write(&mut buffer).expect("Could not write symbols");
+    let resolver = Resolver::from_buffer(&buffer).expect("Could not load symbols");
+    assert!(!resolver.corrupt());
+
+    let info = resolver.lookup(0x2004).expect("Missing symbol");
+    assert_eq!(info.inlined_frames[0].function_name, "inlined");
+}
+
+#[test]
+fn merge_different_modules() {
+    let first = SymbolFile::parse(
+        b"MODULE Linux x86_64 0D4A5B2F5E2B4DA3A0E5D1A2B3C4D5E60 test\n",
+    ).unwrap();
+    let second = SymbolFile::parse(
+        b"MODULE Linux x86_64 1D4A5B2F5E2B4DA3A0E5D1A2B3C4D5E60 test\n",
+    ).unwrap();
+
+    assert!(SymbolFile::merge(&[first, second], &MergeOptions::default()).is_err());
+}
@@ -0,0 +1,144 @@
+extern crate breakpad;
+extern crate difference;
+
+mod common;
+
+use std::fs::File;
+use std::io::prelude::*;
+
+use breakpad::{Resolver, SymbolFile, SymbolIndex};
+use common::fixture_path;
+
+fn load_fixture_buffer(file_name: &str) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let mut file = File::open(fixture_path(file_name)).unwrap();
+    file.read_to_end(&mut buffer).unwrap();
+    buffer
+}
+
+fn convert(buffer: &[u8]) -> Vec<u8> {
+    let mut index = Vec::new();
+    SymbolIndex::convert(buffer, &mut index).expect("Could not convert symbols");
+    index
+}
+
+/// Address, size, line and file id of a line record.
+type LineEntry = (u64, u64, u32, u32);
+
+/// Address, size, name and line records of a function.
+type FunctionEntry = (u64, u64, String, Vec<LineEntry>);
+
+/// Returns address, size, name and line records of all functions.
+fn functions(resolver: &Resolver) -> Vec<FunctionEntry> {
+    resolver
+        .functions()
+        .map(|function| {
+            let lines = function
+                .lines()
+                .map(|line| (line.address(), line.size(), line.line(), line.file_id()))
+                .collect();
+            (function.address(), function.size(), function.name().to_string(), lines)
+        })
+        .collect()
+}
+
+/// Returns address, parameter size and name of all public symbols.
+fn publics(resolver: &Resolver) -> Vec<(u64, u64, String)> {
+    resolver
+        .public_symbols()
+        .map(|public| (public.address(), public.parameter_size(), public.name().to_string()))
+        .collect()
+}
+
+/// Asserts that lookups in the index match the text symbols for all
+/// addresses in the given range.
+fn assert_same_lookups(buffer: &[u8], start: u64, end: u64) {
+    let text = Resolver::from_buffer(buffer).expect("Could not load symbols");
+    let index = Resolver::from_index(convert(buffer)).expect("Could not load index");
+
+    assert_eq!(index.module(), text.module());
+    assert_eq!(index.files().collect::<Vec<_>>(), text.files().collect::<Vec<_>>());
+    assert_eq!(functions(&index), functions(&text));
+    assert_eq!(publics(&index), publics(&text));
+    for address in start..end {
+        assert_eq!(index.lookup(address), text.lookup(address), "at {:#x}", address);
+    }
+}
+
+#[test]
+fn lookup_function_symbols() {
+    let buffer = load_fixture_buffer("crash_macos_func.sym");
+    assert_same_lookups(&buffer, 0x1900, 0x1a00);
+
+    let resolver = Resolver::from_index(convert(&buffer)).expect("Could not load index");
+    assert!(!resolver.corrupt());
+    let info = resolver.lookup(0x19b8).expect("Could not find function");
+    assert_eq!(info.function_name, "(anonymous namespace)::CrashFunction()");
+    assert_eq!(info.source_line, Some(10));
+}
+
+#[test]
+fn lookup_dumped_symbols() {
+    let binary = load_fixture_buffer("hello_linux");
+    let symbols = SymbolFile::from_elf(&binary, "hello_linux").expect("Could not dump symbols");
+    let mut buffer = Vec::new();
+    symbols.write(&mut buffer).expect("Could not write symbols");
+
+    assert_same_lookups(&buffer, 0, 0x1000);
+}
+
+#[test]
+fn lookup_inlined_symbols() {
+    let buffer = b"MODULE Linux x86_64 E8E322F36CBC6413E1D889A90F3856B20 test\n\
+                   INFO CODE_ID F322E3E8BC6C1364E1D889A90F3856B251FF05FD\n\
+                   FILE 0 main.cpp\n\
+                   FILE 1 helpers.h\n\
+                   INLINE_ORIGIN 0 outer()\n\
+                   INLINE_ORIGIN 1 inner()\n\
+                   FUNC 1000 40 0 main\n\
+                   INLINE 0 10 0 0 1010 20\n\
+                   INLINE 1 20 1 1 1018 8\n\
+                   1000 10 5 0\n\
+                   1010 8 21 1\n\
+                   1018 8 30 1\n\
+                   1020 20 11 0\n\
+                   PUBLIC 800 0 before\n\
+                   PUBLIC 2000 0 after\n";
+
+    assert_same_lookups(buffer, 0x700, 0x2100);
+    assert_eq!(publics(&Resolver::from_index(convert(buffer)).unwrap()).len(), 2);
+
+    let resolver = Resolver::from_index(convert(buffer)).expect("Could not load index");
+    let info = resolver.lookup(0x101c).expect("Could not find function");
+    assert_eq!(info.inlined_frames.len(), 2);
+    assert_eq!(info.inlined_frames[0].function_name, "inner()");
+    assert_eq!(resolver.file_name(1), Some("helpers.h"));
+    assert_eq!(resolver.file_name(2), None);
+}
+
+#[test]
+fn find_cfi_records() {
+    let buffer = load_fixture_buffer("crash_macos_cfi.sym");
+    let symbols = SymbolFile::parse(&buffer).expect("Could not parse symbols");
+    let data = convert(&buffer);
+    let index = SymbolIndex::parse(&data).expect("Could not open index");
+
+    for record in &symbols.stack_cfi {
+        assert_eq!(index.find_cfi(record.address).as_ref(), Some(record));
+        assert_eq!(index.find_cfi(record.address + record.size - 1).as_ref(), Some(record));
+    }
+
+    assert_eq!(index.find_cfi(0), None);
+}
+
+#[test]
+fn reject_invalid_index() {
+    let buffer = load_fixture_buffer("crash_macos_func.sym");
+    assert!(SymbolIndex::parse(&buffer).is_err());
+    assert!(Resolver::from_index(buffer).is_err());
+
+    let mut data = convert(&load_fixture_buffer("crash_macos_func.sym"));
+    let len = data.len();
+    data.truncate(len - 8);
+    assert!(SymbolIndex::parse(&data).is_err());
+}